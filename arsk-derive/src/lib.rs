@@ -0,0 +1,217 @@
+extern crate proc_macro;
+extern crate proc_macro2;
+#[macro_use]
+extern crate quote;
+#[macro_use]
+extern crate syn;
+
+use proc_macro::TokenStream;
+use proc_macro2::TokenStream as Tokens;
+use syn::spanned::Spanned;
+use syn::{Data, DeriveInput, Expr, Fields, Lit, LitStr, UnOp};
+
+/// Derives `arsk::Ask` for structs with named fields, asking one question per
+/// field, or `arsk::Field` for enums of unit variants, asked as a select.
+#[proc_macro_derive(Ask, attributes(ask))]
+pub fn derive_ask(input: TokenStream) -> TokenStream {
+    let input = parse_macro_input!(input as DeriveInput);
+    let expanded = match input.data {
+        Data::Struct(_) => expand_struct(&input),
+        Data::Enum(_) => expand_enum(&input),
+        Data::Union(_) => Err(syn::Error::new(
+            input.span(),
+            "Ask cannot be derived for unions",
+        )),
+    };
+    expanded
+        .unwrap_or_else(syn::Error::into_compile_error)
+        .into()
+}
+
+/// What an `#[ask(...)]` attribute says about a field or variant.
+#[derive(Default)]
+struct Options {
+    message: Option<LitStr>,
+    default: Option<String>,
+    validate: Option<Expr>,
+    label: Option<LitStr>,
+    secret: bool,
+    skip: bool,
+}
+
+fn options(attrs: &[syn::Attribute]) -> syn::Result<Options> {
+    let mut options = Options::default();
+    for attr in attrs.iter().filter(|attr| attr.path().is_ident("ask")) {
+        attr.parse_nested_meta(|meta| {
+            if meta.path.is_ident("message") {
+                options.message = Some(meta.value()?.parse()?);
+            } else if meta.path.is_ident("default") {
+                options.default = Some(default_text(&meta.value()?.parse()?)?);
+            } else if meta.path.is_ident("validate") {
+                options.validate = Some(meta.value()?.parse()?);
+            } else if meta.path.is_ident("label") {
+                options.label = Some(meta.value()?.parse()?);
+            } else if meta.path.is_ident("secret") {
+                options.secret = true;
+            } else if meta.path.is_ident("skip") {
+                options.skip = true;
+            } else {
+                return Err(meta.error("unknown ask option"));
+            }
+            Ok(())
+        })?;
+    }
+    Ok(options)
+}
+
+/// The answer a default literal stands for, as it would be typed.
+fn default_text(expr: &Expr) -> syn::Result<String> {
+    let (negative, lit) = match *expr {
+        Expr::Lit(ref lit) => (false, &lit.lit),
+        Expr::Unary(ref unary) => match (&unary.op, &*unary.expr) {
+            (UnOp::Neg(_), Expr::Lit(lit)) => (true, &lit.lit),
+            _ => return Err(syn::Error::new(expr.span(), "default must be a literal")),
+        },
+        _ => return Err(syn::Error::new(expr.span(), "default must be a literal")),
+    };
+    let text = match *lit {
+        Lit::Str(ref s) => s.value(),
+        Lit::Char(ref c) => c.value().to_string(),
+        Lit::Int(ref n) => n.base10_digits().to_string(),
+        Lit::Float(ref n) => n.base10_digits().to_string(),
+        Lit::Bool(ref b) => (if b.value { "y" } else { "n" }).to_string(),
+        _ => return Err(syn::Error::new(lit.span(), "unsupported default")),
+    };
+    Ok(if negative { format!("-{}", text) } else { text })
+}
+
+/// Turns `listen_port` into `Listen port`.
+fn humanise(name: &str) -> String {
+    let words = name.trim_start_matches("r#").replace('_', " ");
+    let mut chars = words.trim().chars();
+    match chars.next() {
+        Some(first) => first.to_uppercase().chain(chars).collect(),
+        None => String::new(),
+    }
+}
+
+fn expand_struct(input: &DeriveInput) -> syn::Result<Tokens> {
+    let fields = match input.data {
+        Data::Struct(ref data) => match data.fields {
+            Fields::Named(ref fields) => &fields.named,
+            _ => {
+                return Err(syn::Error::new(
+                    input.span(),
+                    "Ask can only be derived for structs with named fields",
+                ))
+            }
+        },
+        _ => unreachable!(),
+    };
+    let mut questions = Vec::new();
+    let mut values = Vec::new();
+    for field in fields {
+        let ident = field.ident.as_ref().unwrap();
+        let ty = &field.ty;
+        let options = options(&field.attrs)?;
+        if options.skip {
+            values.push(quote! { #ident: ::std::default::Default::default() });
+            continue;
+        }
+        let key = ident.to_string();
+        let message = match options.message {
+            Some(message) => message.value(),
+            None => humanise(&key),
+        };
+        let mut setters = Vec::new();
+        if let Some(default) = options.default {
+            setters.push(quote! { question.default(#default); });
+        }
+        if let Some(validate) = options.validate {
+            setters.push(quote! { question.validate_with(#validate); });
+        }
+        if options.secret {
+            setters.push(quote! { question.secret(); });
+        }
+        let question = quote! { <#ty as ::arsk::Field>::question(form, #key, #message) };
+        questions.push(match setters.is_empty() {
+            true => quote! { #question; },
+            false => quote! {
+                {
+                    let question = #question;
+                    #(#setters)*
+                }
+            },
+        });
+        values.push(quote! {
+            #ident: <#ty as ::arsk::Field>::from_answer(answers, #key)?
+        });
+    }
+    let name = &input.ident;
+    let (impl_generics, ty_generics, where_clause) = input.generics.split_for_impl();
+    Ok(quote! {
+        impl #impl_generics ::arsk::Ask for #name #ty_generics #where_clause {
+            fn questions(form: &mut ::arsk::Form) {
+                #(#questions)*
+            }
+
+            fn from_answers(answers: &::arsk::Answers) -> ::arsk::Result<Self> {
+                Ok(Self { #(#values),* })
+            }
+        }
+    })
+}
+
+fn expand_enum(input: &DeriveInput) -> syn::Result<Tokens> {
+    let variants = match input.data {
+        Data::Enum(ref data) => &data.variants,
+        _ => unreachable!(),
+    };
+    let mut labels = Vec::new();
+    let mut arms = Vec::new();
+    for (index, variant) in variants.iter().enumerate() {
+        if let Fields::Named(_) | Fields::Unnamed(_) = variant.fields {
+            return Err(syn::Error::new(
+                variant.span(),
+                "Ask can only be derived for enums whose variants have no fields",
+            ));
+        }
+        let ident = &variant.ident;
+        let label = match options(&variant.attrs)?.label {
+            Some(label) => label.value(),
+            None => ident.to_string(),
+        };
+        labels.push(label);
+        arms.push(quote! { Some(#index) => Ok(Self::#ident) });
+    }
+    let name = &input.ident;
+    Ok(quote! {
+        impl ::arsk::Field for #name {
+            fn question<'q, 'f>(
+                form: &'q mut ::arsk::Form<'f>,
+                key: &'f str,
+                message: &'f str,
+            ) -> &'q mut ::arsk::Question<'f> {
+                form.select(key, message, &[#(#labels),*])
+            }
+
+            fn from_answer(answers: &::arsk::Answers, key: &str) -> ::arsk::Result<Self> {
+                match answers.get(key).and_then(|answer| answer.index()) {
+                    #(#arms,)*
+                    _ => Err(::arsk::Error::Missing(key.to_string())),
+                }
+            }
+        }
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::humanise;
+
+    #[test]
+    fn turns_field_names_into_messages() {
+        assert_eq!(humanise("listen_port"), "Listen port");
+        assert_eq!(humanise("r#type"), "Type");
+    }
+}
@@ -0,0 +1,70 @@
+extern crate arsk;
+#[macro_use]
+extern crate arsk_derive;
+
+use arsk::validators::InRange;
+use arsk::Ask as _;
+use arsk::{Error, Form};
+use std::io::Cursor;
+
+#[derive(Debug, PartialEq, Ask)]
+enum Region {
+    #[ask(label = "EU (Ireland)")]
+    Eu,
+    Us,
+}
+
+#[derive(Debug, PartialEq, Ask)]
+struct Config {
+    name: String,
+    #[ask(message = "Port", default = 8080, validate = InRange::new(1, 65535))]
+    listen_port: u16,
+    #[ask(secret)]
+    password: String,
+    region: Region,
+    #[ask(default = true)]
+    tls: bool,
+    certificate: Option<String>,
+    hosts: Vec<String>,
+    #[ask(skip)]
+    retries: u8,
+}
+
+fn ask(typed: &'static [u8], out: &mut Vec<u8>) -> arsk::Result<Config> {
+    let mut form = Form::new();
+    form.redirect_in(Cursor::new(typed)).redirect_out(out);
+    Config::ask_in(&mut form)
+}
+
+#[test]
+fn fills_in_a_struct() {
+    let mut out = Vec::new();
+    let config = ask(b"web\n0\n\nhunter2\n1\n\n\ndb1, db2\n", &mut out).unwrap();
+    assert_eq!(
+        config,
+        Config {
+            name: "web".to_string(),
+            listen_port: 8080,
+            password: "hunter2".to_string(),
+            region: Region::Eu,
+            tls: true,
+            certificate: None,
+            hosts: vec!["db1".to_string(), "db2".to_string()],
+            retries: 0,
+        }
+    );
+    let out = String::from_utf8(out).unwrap();
+    assert!(out.starts_with(
+        "Name\nPort [8080]\nEnter a number from 1 to 65535.\nPort [8080]\nPassword\n"
+    ));
+    assert!(out.contains("1) EU (Ireland)\n"));
+}
+
+#[test]
+fn stops_when_input_runs_out() {
+    let mut out = Vec::new();
+    match ask(b"web\n", &mut out) {
+        Err(Error::Eof) => (),
+        other => panic!("unexpected result: {:?}", other.map(|_| ())),
+    }
+}
@@ -0,0 +1,193 @@
+use std::error::Error as StdError;
+use std::fmt::Display;
+use std::str::FromStr;
+use {Answers, Error, Form, Question, Result};
+
+/// A type filled in by asking a form's worth of questions, usually through
+/// `#[derive(Ask)]`.
+pub trait Ask: Sized {
+    /// Adds a question for each field to `form`.
+    fn questions(form: &mut Form);
+
+    /// Builds a value from the answers to its questions.
+    fn from_answers(answers: &Answers) -> Result<Self>;
+
+    fn ask() -> Result<Self> {
+        Self::ask_in(&mut Form::new())
+    }
+
+    /// Asks through `form`, after any questions it already has, sharing its
+    /// input, output and theme.
+    fn ask_in(form: &mut Form) -> Result<Self> {
+        Self::questions(form);
+        Self::from_answers(&form.ask()?)
+    }
+}
+
+/// A type that can be one field of an `Ask` type, asked with a single question.
+pub trait Field: Sized {
+    fn question<'q, 'f>(
+        form: &'q mut Form<'f>,
+        key: &'f str,
+        message: &'f str,
+    ) -> &'q mut Question<'f>;
+
+    fn from_answer(answers: &Answers, key: &str) -> Result<Self>;
+}
+
+fn missing(key: &str) -> Error {
+    Error::Missing(key.to_string())
+}
+
+impl Field for String {
+    fn question<'q, 'f>(
+        form: &'q mut Form<'f>,
+        key: &'f str,
+        message: &'f str,
+    ) -> &'q mut Question<'f> {
+        form.text(key, message)
+    }
+
+    fn from_answer(answers: &Answers, key: &str) -> Result<String> {
+        answers
+            .get(key)
+            .and_then(|answer| answer.as_str())
+            .map(str::to_string)
+            .ok_or_else(|| missing(key))
+    }
+}
+
+impl Field for bool {
+    fn question<'q, 'f>(
+        form: &'q mut Form<'f>,
+        key: &'f str,
+        message: &'f str,
+    ) -> &'q mut Question<'f> {
+        form.confirm(key, message)
+    }
+
+    fn from_answer(answers: &Answers, key: &str) -> Result<bool> {
+        answers
+            .get(key)
+            .and_then(|answer| answer.as_bool())
+            .ok_or_else(|| missing(key))
+    }
+}
+
+macro_rules! typed_fields {
+    ($($t:ty)*) => {$(
+        impl Field for $t {
+            fn question<'q, 'f>(
+                form: &'q mut Form<'f>,
+                key: &'f str,
+                message: &'f str,
+            ) -> &'q mut Question<'f> {
+                form.typed::<$t>(key, message)
+            }
+
+            fn from_answer(answers: &Answers, key: &str) -> Result<$t> {
+                answers
+                    .get(key)
+                    .and_then(|answer| answer.get::<$t>().cloned().or_else(|| answer.parse()))
+                    .ok_or_else(|| missing(key))
+            }
+        }
+    )*};
+}
+
+typed_fields!(char i8 i16 i32 i64 i128 isize u8 u16 u32 u64 u128 usize f32 f64);
+
+/// Asked like `T`, but may be skipped with an empty answer.
+impl<T: Field> Field for Option<T> {
+    fn question<'q, 'f>(
+        form: &'q mut Form<'f>,
+        key: &'f str,
+        message: &'f str,
+    ) -> &'q mut Question<'f> {
+        T::question(form, key, message).optional()
+    }
+
+    fn from_answer(answers: &Answers, key: &str) -> Result<Option<T>> {
+        match answers.contains_key(key) {
+            true => T::from_answer(answers, key).map(Some),
+            false => Ok(None),
+        }
+    }
+}
+
+/// Asked as a comma-separated list.
+impl<T> Field for Vec<T>
+where
+    T: FromStr + Display,
+    T::Err: Into<Box<dyn StdError + Send + Sync>>,
+{
+    fn question<'q, 'f>(
+        form: &'q mut Form<'f>,
+        key: &'f str,
+        message: &'f str,
+    ) -> &'q mut Question<'f> {
+        form.list::<T>(key, message)
+    }
+
+    fn from_answer(answers: &Answers, key: &str) -> Result<Vec<T>> {
+        let items = answers
+            .get(key)
+            .and_then(|answer| answer.as_list())
+            .ok_or_else(|| missing(key))?;
+        items
+            .iter()
+            .map(|item| {
+                item.parse::<T>().map_err(|err| {
+                    let err: Box<dyn StdError + Send + Sync> = err.into();
+                    let reason = format!("{} in {} is not valid: {}", item, key, err);
+                    Error::Parse(reason.into())
+                })
+            })
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::Field;
+    use std::io::Cursor;
+    use {Answers, Error, Form, Value};
+
+    #[test]
+    fn asks_fields_by_type() {
+        let mut sink = ::std::io::sink();
+        let mut form = Form::new();
+        String::question(&mut form, "name", "Name");
+        <Option<u16>>::question(&mut form, "port", "Port");
+        <Vec<u8>>::question(&mut form, "ids", "Ids");
+        bool::question(&mut form, "tls", "Use TLS?");
+        <Option<bool>>::question(&mut form, "debug", "Debug?");
+        let answers = form
+            .redirect_in(Cursor::new(&b"web\n\n1, 2,3\nn\n\n"[..]))
+            .redirect_out(&mut sink)
+            .ask()
+            .unwrap();
+        assert_eq!(String::from_answer(&answers, "name").unwrap(), "web");
+        assert_eq!(<Option<u16>>::from_answer(&answers, "port").unwrap(), None);
+        assert_eq!(
+            <Vec<u8>>::from_answer(&answers, "ids").unwrap(),
+            vec![1, 2, 3]
+        );
+        assert!(!bool::from_answer(&answers, "tls").unwrap());
+        assert_eq!(
+            <Option<bool>>::from_answer(&answers, "debug").unwrap(),
+            None
+        );
+        assert!(u8::from_answer(&answers, "name").is_err());
+    }
+
+    #[test]
+    fn names_list_items_that_do_not_parse() {
+        let mut answers = Answers::new();
+        let items = vec!["1".to_string(), "two".to_string()];
+        answers.insert("ids".to_string(), Value::List(items));
+        let err = <Vec<u8>>::from_answer(&answers, "ids").unwrap_err();
+        assert!(matches!(err, Error::Parse(_)));
+        assert!(err.to_string().contains("two in ids is not valid"));
+    }
+}
@@ -0,0 +1,145 @@
+use std::fs;
+use std::path::Path;
+
+/// Suggests completions for the line being edited.
+pub trait Completer {
+    /// Returns where the text to be replaced starts, as a byte offset into
+    /// `line` no greater than `cursor`, along with the candidates to replace it.
+    fn complete(&self, line: &str, cursor: usize) -> (usize, Vec<String>);
+}
+
+/// Completes the word under the cursor from a fixed list of words.
+pub struct WordCompleter<'w> {
+    words: &'w [&'w str],
+}
+
+impl<'w> WordCompleter<'w> {
+    pub fn new(words: &'w [&'w str]) -> WordCompleter<'w> {
+        WordCompleter { words }
+    }
+}
+
+impl<'w> Completer for WordCompleter<'w> {
+    fn complete(&self, line: &str, cursor: usize) -> (usize, Vec<String>) {
+        let start = word_start(line, cursor);
+        let word = &line[start..cursor];
+        let candidates = self
+            .words
+            .iter()
+            .filter(|w| w.starts_with(word))
+            .map(|w| w.to_string())
+            .collect();
+        (start, candidates)
+    }
+}
+
+/// Completes the filesystem path under the cursor, adding a `/` to directories.
+#[derive(Default)]
+pub struct PathCompleter;
+
+impl PathCompleter {
+    pub fn new() -> PathCompleter {
+        PathCompleter
+    }
+}
+
+impl Completer for PathCompleter {
+    fn complete(&self, line: &str, cursor: usize) -> (usize, Vec<String>) {
+        let start = word_start(line, cursor);
+        let word = &line[start..cursor];
+        let (dir, file) = match word.rfind('/') {
+            Some(i) => word.split_at(i + 1),
+            None => ("", word),
+        };
+        let entries = match fs::read_dir(if dir.is_empty() { "." } else { dir }) {
+            Ok(entries) => entries,
+            Err(_) => return (start, Vec::new()),
+        };
+        let mut candidates: Vec<String> = entries
+            .filter_map(|entry| entry.ok())
+            .filter_map(|entry| {
+                let name = entry.file_name().into_string().ok()?;
+                if !name.starts_with(file) || (file.is_empty() && name.starts_with('.')) {
+                    return None;
+                }
+                let slash = match Path::new(dir).join(&name).is_dir() {
+                    true => "/",
+                    false => "",
+                };
+                Some(format!("{}{}{}", dir, name, slash))
+            })
+            .collect();
+        candidates.sort();
+        (start, candidates)
+    }
+}
+
+fn word_start(line: &str, cursor: usize) -> usize {
+    line[..cursor]
+        .rfind(char::is_whitespace)
+        .map(|i| i + line[i..].chars().next().map_or(1, char::len_utf8))
+        .unwrap_or(0)
+}
+
+/// The longest prefix shared by every candidate.
+pub(crate) fn common_prefix(candidates: &[String]) -> String {
+    let first = match candidates.first() {
+        Some(first) => first,
+        None => return String::new(),
+    };
+    let mut len = first.len();
+    for candidate in &candidates[1..] {
+        len = first
+            .char_indices()
+            .zip(candidate.chars())
+            .take_while(|&((_, a), b)| a == b)
+            .last()
+            .map_or(0, |((i, a), _)| i + a.len_utf8())
+            .min(len);
+    }
+    first[..len].to_string()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{common_prefix, Completer, PathCompleter, WordCompleter};
+    use std::env;
+    use std::fs;
+    use std::process;
+
+    #[test]
+    fn completes_words() {
+        let words = ["staging", "stable", "prod"];
+        let completer = WordCompleter::new(&words);
+        assert_eq!(
+            completer.complete("deploy st", 9),
+            (7, vec!["staging".to_string(), "stable".to_string()])
+        );
+        assert_eq!(completer.complete("x", 1), (0, Vec::new()));
+    }
+
+    #[test]
+    fn completes_paths() {
+        let dir = env::temp_dir().join(format!("arsk-complete-{}", process::id()));
+        fs::create_dir_all(dir.join("logs")).unwrap();
+        fs::write(dir.join("log.txt"), "").unwrap();
+        let line = format!("tail {}/lo", dir.display());
+        let (start, candidates) = PathCompleter::new().complete(&line, line.len());
+        assert_eq!(start, 5);
+        assert_eq!(
+            candidates,
+            vec![
+                format!("{}/log.txt", dir.display()),
+                format!("{}/logs/", dir.display()),
+            ]
+        );
+        fs::remove_dir_all(dir).unwrap();
+    }
+
+    #[test]
+    fn finds_the_common_prefix() {
+        let candidates = vec!["staging".to_string(), "stable".to_string()];
+        assert_eq!(common_prefix(&candidates), "sta");
+        assert_eq!(common_prefix(&[]), "");
+    }
+}
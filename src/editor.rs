@@ -0,0 +1,426 @@
+use complete::{common_prefix, Completer};
+use hint::Hinter;
+use keys::Key;
+use std::io;
+use std::sync::Mutex;
+use style::Style;
+use terminal::Terminal;
+use {Error, Result};
+
+/// Answers given in this process by prompts without a history key.
+static HISTORY: Mutex<Vec<String>> = Mutex::new(Vec::new());
+
+#[derive(Debug, PartialEq)]
+pub(crate) enum Action {
+    Edit,
+    Submit,
+    Interrupt,
+    Eof,
+    List(Vec<String>),
+}
+
+#[derive(Default)]
+pub(crate) struct LineEditor<'c> {
+    line: Vec<char>,
+    cursor: usize,
+    history: Vec<String>,
+    recalled: Option<usize>,
+    draft: Vec<char>,
+    completer: Option<&'c dyn Completer>,
+    hinter: Option<&'c dyn Hinter>,
+    tabbed: bool,
+}
+
+impl<'c> LineEditor<'c> {
+    pub fn new(
+        history: Vec<String>,
+        completer: Option<&'c dyn Completer>,
+        hinter: Option<&'c dyn Hinter>,
+    ) -> LineEditor<'c> {
+        LineEditor {
+            history,
+            completer,
+            hinter,
+            ..Default::default()
+        }
+    }
+
+    pub fn line(&self) -> String {
+        self.line.iter().collect()
+    }
+
+    fn word_start(&self) -> usize {
+        let mut i = self.cursor;
+        while i > 0 && self.line[i - 1].is_whitespace() {
+            i -= 1;
+        }
+        while i > 0 && !self.line[i - 1].is_whitespace() {
+            i -= 1;
+        }
+        i
+    }
+
+    fn word_end(&self) -> usize {
+        let mut i = self.cursor;
+        while i < self.line.len() && self.line[i].is_whitespace() {
+            i += 1;
+        }
+        while i < self.line.len() && !self.line[i].is_whitespace() {
+            i += 1;
+        }
+        i
+    }
+
+    fn recall(&mut self, index: Option<usize>) {
+        if self.recalled.is_none() {
+            self.draft = self.line.clone();
+        }
+        self.recalled = index;
+        self.line = match index {
+            Some(i) => self.history[i].chars().collect(),
+            None => self.draft.clone(),
+        };
+        self.cursor = self.line.len();
+    }
+
+    /// Completes as far as every candidate agrees, or lists the candidates when
+    /// that gets no further and Tab was also the previous key.
+    fn complete(&mut self, tabbed: bool) -> Action {
+        let completer = match self.completer {
+            Some(completer) => completer,
+            None => return Action::Edit,
+        };
+        let line = self.line();
+        let cursor = self.line[..self.cursor].iter().map(|c| c.len_utf8()).sum();
+        let (start, candidates) = completer.complete(&line, cursor);
+        let start = line[..start.min(cursor)].chars().count();
+        let prefix: Vec<char> = common_prefix(&candidates).chars().collect();
+        if prefix.len() > self.cursor - start {
+            self.line.splice(start..self.cursor, prefix.iter().cloned());
+            self.cursor = start + prefix.len();
+            return Action::Edit;
+        }
+        match tabbed && candidates.len() > 1 {
+            true => Action::List(candidates),
+            false => Action::Edit,
+        }
+    }
+
+    /// The suggestion to show after the line, only offered with the cursor at the end.
+    pub fn hint(&self) -> Option<String> {
+        match (self.hinter, self.cursor == self.line.len()) {
+            (Some(hinter), true) if !self.line.is_empty() => {
+                hinter.hint(&self.line(), &self.history)
+            }
+            _ => None,
+        }
+    }
+
+    pub fn handle(&mut self, key: Key) -> Action {
+        let len = self.line.len();
+        let tabbed = ::std::mem::replace(&mut self.tabbed, key == Key::Tab);
+        match key {
+            Key::Char(c) => {
+                self.line.insert(self.cursor, c);
+                self.cursor += 1;
+            }
+            Key::Right | Key::Ctrl('f') | Key::End | Key::Ctrl('e') if self.cursor == len => {
+                if let Some(hint) = self.hint() {
+                    self.line.extend(hint.chars());
+                    self.cursor = self.line.len();
+                }
+            }
+            Key::Left | Key::Ctrl('b') => self.cursor = self.cursor.saturating_sub(1),
+            Key::Right | Key::Ctrl('f') => self.cursor = (self.cursor + 1).min(len),
+            Key::WordLeft => self.cursor = self.word_start(),
+            Key::WordRight => self.cursor = self.word_end(),
+            Key::Home | Key::Ctrl('a') => self.cursor = 0,
+            Key::End | Key::Ctrl('e') => self.cursor = len,
+            Key::Backspace | Key::Ctrl('h') if self.cursor > 0 => {
+                self.cursor -= 1;
+                self.line.remove(self.cursor);
+            }
+            Key::Ctrl('d') if len == 0 => return Action::Eof,
+            Key::Delete | Key::Ctrl('d') if self.cursor < len => {
+                self.line.remove(self.cursor);
+            }
+            Key::Ctrl('u') => {
+                self.line.drain(..self.cursor);
+                self.cursor = 0;
+            }
+            Key::Ctrl('k') => self.line.truncate(self.cursor),
+            Key::Ctrl('w') => {
+                let start = self.word_start();
+                self.line.drain(start..self.cursor);
+                self.cursor = start;
+            }
+            Key::Up | Key::Ctrl('p') => {
+                let previous = match self.recalled {
+                    None => self.history.len().checked_sub(1),
+                    Some(i) => Some(i.saturating_sub(1)),
+                };
+                if previous.is_some() {
+                    self.recall(previous);
+                }
+            }
+            Key::Down | Key::Ctrl('n') => match self.recalled {
+                Some(i) if i + 1 < self.history.len() => self.recall(Some(i + 1)),
+                Some(_) => self.recall(None),
+                None => (),
+            },
+            Key::Tab => return self.complete(tabbed),
+            Key::Enter => return Action::Submit,
+            Key::Ctrl('c') | Key::Escape => return Action::Interrupt,
+            _ => (),
+        }
+        Action::Edit
+    }
+
+    /// Redraws the line from the start of the row, followed by the hint unless
+    /// `hint_style` is `None`, and puts the cursor back.
+    pub fn draw(
+        &self,
+        terminal: &mut dyn Terminal,
+        style: &Style,
+        hint_style: Option<&Style>,
+    ) -> io::Result<()> {
+        terminal.write("\r\x1b[K")?;
+        if !self.line.is_empty() {
+            terminal.write_styled(&self.line(), style)?;
+        }
+        let mut back = self.line.len() - self.cursor;
+        if let (Some(hint), Some(hint_style)) = (self.hint(), hint_style) {
+            terminal.write_styled(&hint, hint_style)?;
+            back += hint.chars().count();
+        }
+        match back {
+            0 => Ok(()),
+            n => terminal.write(&format!("\x1b[{}D", n)),
+        }
+    }
+}
+
+/// Answers given through prompts without a history key, oldest first.
+pub(crate) fn history() -> Vec<String> {
+    HISTORY.lock().map(|h| h.clone()).unwrap_or_default()
+}
+
+pub(crate) fn remember(line: &str) {
+    if let Ok(mut history) = HISTORY.lock() {
+        if !line.is_empty() && history.last().map(String::as_str) != Some(line) {
+            history.push(line.to_string());
+        }
+    }
+}
+
+/// Reads a line with `editor`, drawing it in `style` and its hints in
+/// `hint_style`. Like `Terminal::read_line`, the line keeps its line ending and is
+/// empty at end of input.
+pub(crate) fn read_line(
+    terminal: &mut dyn Terminal,
+    mut editor: LineEditor,
+    style: &Style,
+    hint_style: &Style,
+) -> Result<String> {
+    loop {
+        editor.draw(terminal, style, Some(hint_style))?;
+        let action = editor.handle(terminal.read_key()?);
+        if action != Action::Edit {
+            editor.draw(terminal, style, None)?;
+        }
+        match action {
+            Action::Edit => (),
+            Action::List(candidates) => {
+                terminal.write(&format!("\n{}\n", candidates.join("  ")))?
+            }
+            Action::Submit => break,
+            Action::Eof => {
+                terminal.write("\n")?;
+                return Ok(String::new());
+            }
+            Action::Interrupt => {
+                terminal.write("\n")?;
+                return Err(Error::Interrupted);
+            }
+        }
+    }
+    terminal.write("\n")?;
+    Ok(editor.line() + "\n")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{history, remember, Action, LineEditor};
+    use input;
+    use keys::Key;
+    use std::io::Cursor;
+    use std::process;
+    use {Colour, Hinter, HistoryHinter, MemoryTerminal, Style, WordCompleter};
+
+    struct Environments;
+
+    impl Hinter for Environments {
+        fn hint(&self, line: &str, _history: &[String]) -> Option<String> {
+            "staging"
+                .get(line.len()..)
+                .filter(|_| "staging".starts_with(line))
+                .map(String::from)
+        }
+    }
+
+    fn edit(editor: &mut LineEditor, keys: &[Key]) {
+        for &key in keys {
+            assert_eq!(editor.handle(key), Action::Edit);
+        }
+    }
+
+    fn type_text(editor: &mut LineEditor, text: &str) {
+        for c in text.chars() {
+            editor.handle(Key::Char(c));
+        }
+    }
+
+    #[test]
+    fn moves_the_cursor() {
+        let mut editor = LineEditor::default();
+        type_text(&mut editor, "helo world");
+        edit(&mut editor, &[Key::WordLeft, Key::Left, Key::Left]);
+        type_text(&mut editor, "l");
+        edit(
+            &mut editor,
+            &[Key::Ctrl('a'), Key::WordRight, Key::Ctrl('e')],
+        );
+        type_text(&mut editor, "!");
+        assert_eq!(editor.line(), "hello world!");
+        edit(&mut editor, &[Key::Home, Key::Right]);
+        let mut term = MemoryTerminal::new("");
+        editor.draw(&mut term, &Style::default(), None).unwrap();
+        assert_eq!(term.output(), "\r\x1b[Khello world!\x1b[11D");
+    }
+
+    #[test]
+    fn deletes_words_and_lines() {
+        let mut editor = LineEditor::default();
+        type_text(&mut editor, "deploy to prod now");
+        edit(&mut editor, &[Key::Ctrl('w')]);
+        assert_eq!(editor.line(), "deploy to prod ");
+        edit(&mut editor, &[Key::WordLeft, Key::Ctrl('k')]);
+        assert_eq!(editor.line(), "deploy to ");
+        edit(&mut editor, &[Key::Left, Key::Ctrl('u')]);
+        assert_eq!(editor.line(), " ");
+        edit(&mut editor, &[Key::Delete, Key::Backspace]);
+        assert_eq!(editor.line(), "");
+        assert_eq!(editor.handle(Key::Ctrl('d')), Action::Eof);
+    }
+
+    #[test]
+    fn recalls_history() {
+        let history = vec!["first".to_string(), "second".to_string()];
+        let mut editor = LineEditor::new(history, None, None);
+        type_text(&mut editor, "draft");
+        edit(&mut editor, &[Key::Up]);
+        assert_eq!(editor.line(), "second");
+        edit(&mut editor, &[Key::Up, Key::Up]);
+        assert_eq!(editor.line(), "first");
+        edit(&mut editor, &[Key::Down, Key::Down]);
+        assert_eq!(editor.line(), "draft");
+    }
+
+    #[test]
+    fn accepts_hints_with_the_right_arrow() {
+        let history = vec!["web1".to_string(), "db1".to_string()];
+        let mut editor = LineEditor::new(history, None, Some(&HistoryHinter));
+        type_text(&mut editor, "w");
+        assert_eq!(editor.hint(), Some("eb1".to_string()));
+        edit(&mut editor, &[Key::Left]);
+        assert_eq!(editor.hint(), None);
+        edit(&mut editor, &[Key::Right, Key::Right]);
+        assert_eq!(editor.line(), "web1");
+        assert_eq!(editor.hint(), None);
+    }
+
+    #[test]
+    fn keeps_hints_out_of_the_answer() {
+        let mut term = MemoryTerminal::new("st\r");
+        term.tty(true);
+        let answer = input("Env")
+            .hinter(&Environments)
+            .hint_colour(Colour::Blue)
+            .terminal(&mut term)
+            .ask()
+            .unwrap();
+        assert_eq!(answer, "st");
+        assert!(term.output().contains("\x1b[Kstaging\x1b[5D"));
+        assert!(term.output().ends_with("\r\x1b[Kst\n"));
+    }
+
+    #[test]
+    fn completes_with_tab() {
+        let words = ["staging", "stable", "prod"];
+        let completer = WordCompleter::new(&words);
+        let mut editor = LineEditor::new(Vec::new(), Some(&completer), None);
+        type_text(&mut editor, "to s");
+        edit(&mut editor, &[Key::Tab]);
+        assert_eq!(editor.line(), "to sta");
+        assert_eq!(
+            editor.handle(Key::Tab),
+            Action::List(vec!["staging".to_string(), "stable".to_string()])
+        );
+        type_text(&mut editor, "g");
+        edit(&mut editor, &[Key::Tab]);
+        assert_eq!(editor.line(), "to staging");
+    }
+
+    #[test]
+    fn lists_candidates_beneath_the_prompt() {
+        let words = ["staging", "stable"];
+        let completer = WordCompleter::new(&words);
+        let mut term = MemoryTerminal::new("s\t\t\r");
+        term.tty(true);
+        let answer = input("Env")
+            .completer(&completer)
+            .terminal(&mut term)
+            .ask()
+            .unwrap();
+        assert_eq!(answer, "sta");
+        assert!(term.output().contains("\nstaging  stable\n"));
+    }
+
+    #[test]
+    fn does_not_complete_redirected_input() {
+        let words = ["staging"];
+        let completer = WordCompleter::new(&words);
+        let mut output = Vec::new();
+        let answer = input("Env")
+            .completer(&completer)
+            .redirect_in(Cursor::new(&b"s\t\n"[..]))
+            .redirect_out(&mut output)
+            .ask()
+            .unwrap();
+        assert_eq!(answer, "s\t");
+    }
+
+    #[test]
+    fn can_edit_an_answer_on_a_terminal() {
+        let mut term = MemoryTerminal::new("helo\x1b[Dl\r");
+        term.tty(true);
+        let answer = input("Greeting")
+            .line_editor()
+            .terminal(&mut term)
+            .ask()
+            .unwrap();
+        assert_eq!(answer, "hello");
+        assert!(!term.is_raw());
+    }
+
+    #[test]
+    fn remembers_answers_for_later_prompts() {
+        // Other tests share this history, so only look for our own answer.
+        let answer = format!("answer-{}", process::id());
+        remember(&answer);
+        remember("");
+        let history = history();
+        assert!(history.contains(&answer));
+        assert!(!history.contains(&String::new()));
+    }
+}
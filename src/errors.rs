@@ -0,0 +1,113 @@
+use std::error;
+use std::fmt;
+use std::io;
+
+/// Everything that can stop a prompt from producing an answer.
+#[derive(Debug)]
+pub enum Error {
+    /// Reading from or writing to the terminal failed.
+    Io(io::Error),
+    /// Input ended before an answer was given.
+    Eof,
+    /// The user pressed Ctrl-C or Escape.
+    Interrupted,
+    /// No acceptable answer was given within the allowed attempts, and there was
+    /// no acceptable default to fall back on.
+    ValidationExhausted { attempts: usize, reason: String },
+    /// An answer never parsed into the type asked for, or something else, such
+    /// as a theme, could not be parsed.
+    Parse(Box<dyn error::Error + Send + Sync>),
+    /// The prompt needs an interactive terminal and does not have one.
+    NotATerminal,
+    /// No answer arrived before the terminal gave up waiting.
+    Timeout,
+    /// The user answered no when asked to confirm.
+    Declined,
+    /// A select prompt was given nothing to choose from.
+    NoOptions,
+    /// The question with this key has no usable answer.
+    Missing(String),
+}
+
+pub type Result<T> = ::std::result::Result<T, Error>;
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match *self {
+            Error::Io(ref err) => write!(f, "Unable to talk to the terminal: {}", err),
+            Error::Eof => write!(f, "Reached end of input"),
+            Error::Interrupted => write!(f, "Interrupted by the user"),
+            Error::ValidationExhausted {
+                attempts,
+                ref reason,
+            } => write!(
+                f,
+                "Response failed validation after {} attempt(s): {}",
+                attempts, reason
+            ),
+            Error::Parse(ref err) => write!(f, "Unable to parse: {}", err),
+            Error::NotATerminal => write!(f, "Not a terminal"),
+            Error::Timeout => write!(f, "Timed out waiting for an answer"),
+            Error::Declined => write!(f, "Confirmation declined"),
+            Error::NoOptions => write!(f, "No options to choose from"),
+            Error::Missing(ref key) => write!(f, "There is no answer for {}", key),
+        }
+    }
+}
+
+impl error::Error for Error {
+    fn source(&self) -> Option<&(dyn error::Error + 'static)> {
+        match *self {
+            Error::Io(ref err) => Some(err),
+            Error::Parse(ref err) => Some(&**err),
+            _ => None,
+        }
+    }
+}
+
+impl From<io::Error> for Error {
+    fn from(err: io::Error) -> Error {
+        match err.kind() {
+            io::ErrorKind::UnexpectedEof => Error::Eof,
+            io::ErrorKind::TimedOut => Error::Timeout,
+            _ if is_not_a_terminal(&err) => Error::NotATerminal,
+            _ => Error::Io(err),
+        }
+    }
+}
+
+#[cfg(unix)]
+fn is_not_a_terminal(err: &io::Error) -> bool {
+    err.raw_os_error() == Some(::libc::ENOTTY)
+}
+
+#[cfg(not(unix))]
+fn is_not_a_terminal(_err: &io::Error) -> bool {
+    false
+}
+
+#[cfg(test)]
+mod tests {
+    use super::Error;
+    use regex;
+    use std::error::Error as StdError;
+    use std::io;
+    use validators::Matches;
+
+    #[test]
+    fn classifies_io_errors() {
+        let eof = io::Error::from(io::ErrorKind::UnexpectedEof);
+        assert!(matches!(Error::from(eof), Error::Eof));
+        let timeout = io::Error::from(io::ErrorKind::TimedOut);
+        assert!(matches!(Error::from(timeout), Error::Timeout));
+        let denied = Error::from(io::Error::from(io::ErrorKind::PermissionDenied));
+        assert!(denied.source().is_some());
+        assert!(denied
+            .to_string()
+            .starts_with("Unable to talk to the terminal"));
+        let pattern = Matches::new("(").err().unwrap();
+        assert!(matches!(pattern, Error::Parse(_)));
+        let source = pattern.source().unwrap();
+        assert!(source.downcast_ref::<regex::Error>().is_some());
+    }
+}
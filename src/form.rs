@@ -0,0 +1,502 @@
+use std::any::Any;
+use std::collections::BTreeMap;
+use std::error::Error as StdError;
+use std::fmt::{self, Display};
+use std::io::{BufRead, Write};
+use std::str::FromStr;
+use std::sync::Arc;
+use {input, Presets, Result, Terminal, Theme, Validator};
+
+/// The answers to a form's questions by key. Skipped questions have no answer.
+pub type Answers = BTreeMap<String, Value>;
+
+/// The answer to one question of a form.
+#[derive(Clone)]
+pub enum Value {
+    /// Text and secret answers.
+    Text(String),
+    /// The parsed value of a typed answer, which `get` gives back, and the text
+    /// it displays as.
+    Typed(Arc<dyn Any + Send + Sync>, String),
+    Bool(bool),
+    /// The index and label of the chosen option.
+    Choice(usize, String),
+    /// The items of a list answer, each kept as its parsed value displays.
+    List(Vec<String>),
+}
+
+impl Value {
+    fn typed<V: Any + Display + Send + Sync>(value: V) -> Value {
+        let text = value.to_string();
+        Value::Typed(Arc::new(value), text)
+    }
+
+    /// The text of an answer, the text a typed answer displays as, or the label
+    /// of a chosen option.
+    pub fn as_str(&self) -> Option<&str> {
+        match *self {
+            Value::Text(ref text) | Value::Typed(_, ref text) | Value::Choice(_, ref text) => {
+                Some(text)
+            }
+            Value::Bool(_) | Value::List(_) => None,
+        }
+    }
+
+    /// The value of a typed answer, if it is a `V`.
+    pub fn get<V: Any>(&self) -> Option<&V> {
+        match *self {
+            Value::Typed(ref value, _) => value.downcast_ref(),
+            _ => None,
+        }
+    }
+
+    pub fn as_bool(&self) -> Option<bool> {
+        match *self {
+            Value::Bool(answer) => Some(answer),
+            _ => None,
+        }
+    }
+
+    /// The index of a chosen option.
+    pub fn index(&self) -> Option<usize> {
+        match *self {
+            Value::Choice(index, _) => Some(index),
+            _ => None,
+        }
+    }
+
+    pub fn as_list(&self) -> Option<&[String]> {
+        match *self {
+            Value::List(ref items) => Some(items),
+            _ => None,
+        }
+    }
+
+    pub fn parse<V: FromStr>(&self) -> Option<V> {
+        self.as_str().and_then(|text| text.parse().ok())
+    }
+}
+
+impl fmt::Debug for Value {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match *self {
+            Value::Text(ref text) => f.debug_tuple("Text").field(text).finish(),
+            Value::Typed(_, ref text) => f.debug_tuple("Typed").field(text).finish(),
+            Value::Bool(answer) => f.debug_tuple("Bool").field(&answer).finish(),
+            Value::Choice(index, ref label) => {
+                f.debug_tuple("Choice").field(&index).field(label).finish()
+            }
+            Value::List(ref items) => f.debug_tuple("List").field(items).finish(),
+        }
+    }
+}
+
+/// Typed answers are equal when they hold the same type and display the same.
+impl PartialEq for Value {
+    fn eq(&self, other: &Value) -> bool {
+        match (self, other) {
+            (Value::Text(a), Value::Text(b)) => a == b,
+            (Value::Typed(a, a_text), Value::Typed(b, b_text)) => {
+                (**a).type_id() == (**b).type_id() && a_text == b_text
+            }
+            (Value::Bool(a), Value::Bool(b)) => a == b,
+            (Value::Choice(a, a_label), Value::Choice(b, b_label)) => a == b && a_label == b_label,
+            (Value::List(a), Value::List(b)) => a == b,
+            _ => false,
+        }
+    }
+}
+
+type ParseError = Box<dyn StdError + Send + Sync>;
+type Parse = fn(&str) -> ::std::result::Result<Value, ParseError>;
+type ParseItem = fn(&str) -> ::std::result::Result<String, ParseError>;
+
+enum Kind {
+    Text,
+    Typed(Parse),
+    List(ParseItem),
+    Select(Vec<String>),
+    Confirm,
+}
+
+fn parse_typed<V>(answer: &str) -> ::std::result::Result<Value, ParseError>
+where
+    V: FromStr + Display + Any + Send + Sync,
+    V::Err: Into<ParseError>,
+{
+    answer.parse::<V>().map(Value::typed).map_err(Into::into)
+}
+
+/// Parses an item of a list, keeping the text its value displays as.
+fn parse_item<V>(item: &str) -> ::std::result::Result<String, ParseError>
+where
+    V: FromStr + Display,
+    V::Err: Into<ParseError>,
+{
+    match item.parse::<V>() {
+        Ok(value) => Ok(value.to_string()),
+        Err(err) => Err(err.into()),
+    }
+}
+
+/// Splits a comma-separated answer into its items, parsing each.
+fn parse_list(answer: &str, parse: ParseItem) -> ::std::result::Result<Vec<String>, ParseError> {
+    answer
+        .split(',')
+        .map(str::trim)
+        .filter(|item| !item.is_empty())
+        .map(parse)
+        .collect()
+}
+
+type Condition<'f> = Box<dyn Fn(&Answers) -> bool + 'f>;
+
+/// One question of a form, configured through the methods that add it.
+pub struct Question<'f> {
+    key: &'f str,
+    message: &'f str,
+    kind: Kind,
+    default: Option<&'f str>,
+    validate: Option<Box<dyn Validator + 'f>>,
+    when: Option<Condition<'f>>,
+    optional: bool,
+    secret: bool,
+}
+
+impl<'f> Question<'f> {
+    fn new(key: &'f str, message: &'f str, kind: Kind) -> Question<'f> {
+        Question {
+            key,
+            message,
+            kind,
+            default: None,
+            validate: None,
+            when: None,
+            optional: false,
+            secret: false,
+        }
+    }
+
+    pub fn default(&mut self, default: &'f str) -> &mut Question<'f> {
+        self.default = Some(default);
+        self
+    }
+
+    pub fn validate(&mut self, validator: &'f dyn Validator) -> &mut Question<'f> {
+        self.validate_with(move |answer: &str| validator.validate(answer))
+    }
+
+    /// Like `validate`, but the question keeps `validator`.
+    pub fn validate_with<V: Validator + 'f>(&mut self, validator: V) -> &mut Question<'f> {
+        self.validate = Some(Box::new(validator));
+        self
+    }
+
+    /// Hides text answers as they are typed.
+    pub fn secret(&mut self) -> &mut Question<'f> {
+        self.secret = true;
+        self
+    }
+
+    /// Lets the question be skipped with an empty answer, leaving it out of the
+    /// answers. Optional selects list their options to be typed rather than
+    /// picked with the arrow keys.
+    pub fn optional(&mut self) -> &mut Question<'f> {
+        self.optional = true;
+        self
+    }
+
+    /// Only asks the question when `condition` holds for the answers so far.
+    pub fn when<F>(&mut self, condition: F) -> &mut Question<'f>
+    where
+        F: Fn(&Answers) -> bool + 'f,
+    {
+        self.when = Some(Box::new(condition));
+        self
+    }
+}
+
+/// A sequence of named questions asked one after another through the same
+/// input, output and theme.
+#[derive(Default)]
+pub struct Form<'f> {
+    questions: Vec<Question<'f>>,
+    theme: Option<&'f Theme>,
+    presets: Option<&'f Presets>,
+    input: Option<Box<dyn BufRead + 'f>>,
+    output: Option<&'f mut dyn Write>,
+    terminal: Option<&'f mut dyn Terminal>,
+}
+
+impl<'f> Form<'f> {
+    pub fn new() -> Form<'f> {
+        Form::default()
+    }
+
+    fn add(&mut self, question: Question<'f>) -> &mut Question<'f> {
+        self.questions.push(question);
+        self.questions.last_mut().unwrap()
+    }
+
+    pub fn text(&mut self, key: &'f str, message: &'f str) -> &mut Question<'f> {
+        self.add(Question::new(key, message, Kind::Text))
+    }
+
+    /// Asks without echoing the answer.
+    pub fn secret(&mut self, key: &'f str, message: &'f str) -> &mut Question<'f> {
+        self.text(key, message).secret()
+    }
+
+    /// Asks until the answer parses as a `V`, which `Value::get` gives back.
+    pub fn typed<V>(&mut self, key: &'f str, message: &'f str) -> &mut Question<'f>
+    where
+        V: FromStr + Display + Any + Send + Sync,
+        V::Err: Into<ParseError>,
+    {
+        self.add(Question::new(key, message, Kind::Typed(parse_typed::<V>)))
+    }
+
+    /// Asks for a comma-separated list of items that each parse as a `V`.
+    pub fn list<V>(&mut self, key: &'f str, message: &'f str) -> &mut Question<'f>
+    where
+        V: FromStr + Display,
+        V::Err: Into<ParseError>,
+    {
+        self.add(Question::new(key, message, Kind::List(parse_item::<V>)))
+    }
+
+    pub fn select<V: Display>(
+        &mut self,
+        key: &'f str,
+        message: &'f str,
+        options: &[V],
+    ) -> &mut Question<'f> {
+        let labels = options.iter().map(|option| option.to_string()).collect();
+        self.add(Question::new(key, message, Kind::Select(labels)))
+    }
+
+    /// Asks a yes/no question.
+    pub fn confirm(&mut self, key: &'f str, message: &'f str) -> &mut Question<'f> {
+        self.add(Question::new(key, message, Kind::Confirm))
+    }
+
+    pub fn theme(&mut self, theme: &'f Theme) -> &mut Form<'f> {
+        self.theme = Some(theme);
+        self
+    }
+
+    /// Takes supplied answers from `presets`, by each question's key, instead of
+    /// those given to `set_presets`.
+    pub fn presets(&mut self, presets: &'f Presets) -> &mut Form<'f> {
+        self.presets = Some(presets);
+        self
+    }
+
+    pub fn redirect_in<R: BufRead + 'f>(&mut self, r: R) -> &mut Form<'f> {
+        self.input = Some(Box::new(r));
+        self
+    }
+
+    pub fn redirect_out<W: Write>(&mut self, w: &'f mut W) -> &mut Form<'f> {
+        self.output = Some(w);
+        self
+    }
+
+    pub fn terminal<U: Terminal>(&mut self, t: &'f mut U) -> &mut Form<'f> {
+        self.terminal = Some(t);
+        self
+    }
+
+    /// Asks each question in turn, skipping those whose condition does not hold.
+    pub fn ask(&mut self) -> Result<Answers> {
+        let mut answers = Answers::new();
+        for index in 0..self.questions.len() {
+            let asked = match self.questions[index].when {
+                Some(ref condition) => condition(&answers),
+                None => true,
+            };
+            if !asked {
+                continue;
+            }
+            if let Some(value) = self.ask_question(index)? {
+                answers.insert(self.questions[index].key.to_string(), value);
+            }
+        }
+        Ok(answers)
+    }
+
+    /// Asks one question, giving `None` when an optional question is skipped.
+    fn ask_question(&mut self, index: usize) -> Result<Option<Value>> {
+        let question = &self.questions[index];
+        let optional = question.optional;
+        let validate = question.validate.as_ref().map(|validator| {
+            move |answer: &str| match answer {
+                "" if optional => Ok(()),
+                _ => validator.validate(answer),
+            }
+        });
+        let mut prompt = input(question.message);
+        prompt.key(question.key);
+        if let Some(theme) = self.theme {
+            prompt.theme(theme);
+        }
+        if let Some(presets) = self.presets {
+            prompt.presets(presets);
+        }
+        if let Some(ref mut r) = self.input {
+            prompt.redirect_in(&mut **r);
+        }
+        if let Some(ref mut w) = self.output {
+            prompt.state.console.output = Some(&mut **w);
+        }
+        if let Some(ref mut t) = self.terminal {
+            prompt.state.console.terminal = Some(&mut **t);
+        }
+        if let Some(default) = question.default {
+            prompt.default(default);
+        }
+        if question.secret {
+            prompt.no_echo();
+        }
+        if let Some(ref validate) = validate {
+            prompt.validate(validate);
+        }
+        let text = |answer: String| match answer.as_str() {
+            "" if optional => None,
+            _ => Some(Value::Text(answer)),
+        };
+        Ok(match question.kind {
+            Kind::Text => text(prompt.ask()?),
+            Kind::Typed(parse) => prompt.parse_with(|answer| match answer {
+                "" if optional => Ok(None),
+                _ => parse(answer).map(Some),
+            })?,
+            Kind::List(parse) => {
+                let items = prompt.parse_with(|answer| parse_list(answer, parse))?;
+                match items.is_empty() {
+                    true if optional => None,
+                    _ => Some(Value::List(items)),
+                }
+            }
+            Kind::Select(ref labels) if optional => prompt
+                .ask_optional_select(labels)?
+                .map(|(index, label)| Value::Choice(index, label.clone())),
+            Kind::Select(ref labels) => {
+                let (index, label) = prompt.ask_select(labels)?;
+                Some(Value::Choice(index, label.clone()))
+            }
+            Kind::Confirm if optional => prompt.ask_optional_yes_no()?.map(Value::Bool),
+            Kind::Confirm => Some(Value::Bool(prompt.ask_yes_no()?)),
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{Form, Value};
+    use std::io::Cursor;
+    use validators::NonEmpty;
+    use {Error, Presets};
+
+    #[test]
+    fn asks_each_question_in_turn() {
+        let mut out = Vec::new();
+        let answers = {
+            let mut form = Form::new();
+            form.text("name", "Name").validate(&NonEmpty);
+            form.secret("password", "Password");
+            form.typed::<u16>("port", "Port").default("8080");
+            form.select("env", "Environment", &["dev", "prod"]);
+            form.confirm("tls", "Use TLS?");
+            form.redirect_in(Cursor::new(&b"\nweb\nhunter2\n\n2\ny\n"[..]))
+                .redirect_out(&mut out)
+                .ask()
+                .unwrap()
+        };
+        assert_eq!(answers["name"], Value::Text("web".to_string()));
+        assert_eq!(answers["password"].as_str(), Some("hunter2"));
+        assert_eq!(answers["port"].get::<u16>(), Some(&8080));
+        assert_eq!(answers["env"], Value::Choice(1, "prod".to_string()));
+        assert_eq!(answers["tls"].as_bool(), Some(true));
+        let out = String::from_utf8(out).unwrap();
+        assert!(out.starts_with("Name\nAn answer is required.\nName\nPassword\nPort [8080]\n"));
+    }
+
+    #[test]
+    fn keeps_typed_answers_as_their_values() {
+        let mut sink = ::std::io::sink();
+        let mut form = Form::new();
+        form.typed::<f64>("ratio", "Ratio");
+        let answers = form
+            .redirect_in(Cursor::new(&b"1e3\n"[..]))
+            .redirect_out(&mut sink)
+            .ask()
+            .unwrap();
+        assert_eq!(answers["ratio"].get::<f64>(), Some(&1000.0));
+        assert_eq!(answers["ratio"].get::<u16>(), None);
+        assert_eq!(answers["ratio"].as_str(), Some("1000"));
+    }
+
+    #[test]
+    fn skips_optional_selects_and_yes_no_questions() {
+        let mut sink = ::std::io::sink();
+        let mut form = Form::new();
+        form.select("region", "Region", &["eu", "us"]).optional();
+        form.confirm("tls", "Use TLS?").optional();
+        form.select("zone", "Zone", &["a", "b"]).optional();
+        form.confirm("debug", "Debug?").optional();
+        let answers = form
+            .redirect_in(Cursor::new(&b"\n\nb\nmaybe\ny\n"[..]))
+            .redirect_out(&mut sink)
+            .ask()
+            .unwrap();
+        assert!(!answers.contains_key("region"));
+        assert!(!answers.contains_key("tls"));
+        assert_eq!(answers["zone"], Value::Choice(1, "b".to_string()));
+        assert_eq!(answers["debug"], Value::Bool(true));
+    }
+
+    #[test]
+    fn skips_questions_by_earlier_answers() {
+        let mut sink = ::std::io::sink();
+        let mut form = Form::new();
+        form.confirm("tls", "Use TLS?");
+        form.text("cert", "Certificate")
+            .when(|answers| answers["tls"].as_bool() == Some(true));
+        form.typed::<u16>("port", "Port");
+        let answers = form
+            .redirect_in(Cursor::new(&b"n\n80\n"[..]))
+            .redirect_out(&mut sink)
+            .ask()
+            .unwrap();
+        assert!(!answers.contains_key("cert"));
+        assert_eq!(answers["port"].parse::<u16>(), Some(80));
+    }
+
+    #[test]
+    fn takes_answers_from_presets() {
+        let mut out = Vec::new();
+        let mut presets =
+            Presets::from_toml("env = \"prod\"\ntls = false\nhosts = [\"db1\", \"db2\"]").unwrap();
+        presets.non_interactive(true);
+        let answers = {
+            let mut form = Form::new();
+            form.select("env", "Environment", &["dev", "prod"]);
+            form.confirm("tls", "Use TLS?");
+            form.list::<String>("hosts", "Hosts");
+            form.typed::<u16>("port", "Port").default("8080");
+            form.presets(&presets).redirect_out(&mut out).ask().unwrap()
+        };
+        assert_eq!(answers["env"], Value::Choice(1, "prod".to_string()));
+        assert_eq!(answers["tls"], Value::Bool(false));
+        assert_eq!(answers["hosts"].as_list().unwrap(), ["db1", "db2"]);
+        assert_eq!(answers["port"].parse::<u16>(), Some(8080));
+        assert!(out.is_empty());
+        let mut form = Form::new();
+        form.text("name", "Name");
+        match form.presets(&presets).ask() {
+            Err(Error::Missing(ref key)) if key == "name" => (),
+            other => panic!("unexpected result: {:?}", other),
+        }
+    }
+}
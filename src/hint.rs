@@ -0,0 +1,33 @@
+/// Suggests how the line being edited might continue, shown greyed out after the
+/// cursor until accepted with the right arrow.
+pub trait Hinter {
+    /// The text to show after `line`, given the prompt's history, oldest first.
+    fn hint(&self, line: &str, history: &[String]) -> Option<String>;
+}
+
+/// Suggests the most recent answer that starts with what has been typed so far.
+#[derive(Default)]
+pub struct HistoryHinter;
+
+impl Hinter for HistoryHinter {
+    fn hint(&self, line: &str, history: &[String]) -> Option<String> {
+        history
+            .iter()
+            .rev()
+            .find(|entry| entry.len() > line.len() && entry.starts_with(line))
+            .map(|entry| entry[line.len()..].to_string())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{Hinter, HistoryHinter};
+
+    #[test]
+    fn hints_from_recent_history() {
+        let history = vec!["web1".to_string(), "db1".to_string(), "web2".to_string()];
+        assert_eq!(HistoryHinter.hint("we", &history), Some("b2".to_string()));
+        assert_eq!(HistoryHinter.hint("db1", &history), None);
+        assert_eq!(HistoryHinter.hint("x", &history), None);
+    }
+}
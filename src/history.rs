@@ -0,0 +1,110 @@
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+
+#[cfg(test)]
+thread_local! {
+    /// Where this test thread keeps history files, instead of the data directory.
+    static TEST_DIR: ::std::cell::RefCell<Option<PathBuf>> = Default::default();
+}
+
+/// The most answers kept for any one history key.
+const MAX_ENTRIES: usize = 500;
+
+/// Answers given to prompts sharing a history key, one per line of a file under
+/// the user's data directory.
+pub(crate) struct History {
+    path: PathBuf,
+}
+
+impl History {
+    pub fn named(key: &str) -> Option<History> {
+        Some(History::at(dir()?.join(file_name(key))))
+    }
+
+    pub fn at<P: AsRef<Path>>(path: P) -> History {
+        History {
+            path: path.as_ref().to_path_buf(),
+        }
+    }
+
+    /// Oldest first, or empty if nothing has been saved yet.
+    pub fn load(&self) -> Vec<String> {
+        fs::read_to_string(&self.path)
+            .map(|text| text.lines().map(str::to_string).collect())
+            .unwrap_or_default()
+    }
+
+    /// Appends `entry`, dropping any earlier copy of it and the oldest entries
+    /// past the cap.
+    pub fn save(&self, entry: &str) -> io::Result<()> {
+        if entry.is_empty() || entry.contains('\n') {
+            return Ok(());
+        }
+        let mut entries = self.load();
+        entries.retain(|e| e != entry);
+        entries.push(entry.to_string());
+        let skip = entries.len().saturating_sub(MAX_ENTRIES);
+        if let Some(dir) = self.path.parent() {
+            fs::create_dir_all(dir)?;
+        }
+        let mut text = entries[skip..].join("\n");
+        text.push('\n');
+        fs::write(&self.path, text)
+    }
+}
+
+#[cfg(not(test))]
+fn dir() -> Option<PathBuf> {
+    Some(dirs::data_dir()?.join("arsk").join("history"))
+}
+
+#[cfg(test)]
+fn dir() -> Option<PathBuf> {
+    TEST_DIR.with(|dir| dir.borrow().clone())
+}
+
+/// Keeps history files for prompts on this test thread in `dir`.
+#[cfg(test)]
+pub(crate) fn use_dir<P: AsRef<Path>>(dir: P) {
+    TEST_DIR.with(|test_dir| *test_dir.borrow_mut() = Some(dir.as_ref().to_path_buf()));
+}
+
+fn file_name(key: &str) -> String {
+    key.chars()
+        .map(|c| match c {
+            'a'..='z' | 'A'..='Z' | '0'..='9' | '-' | '_' => c,
+            _ => '_',
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{file_name, History, MAX_ENTRIES};
+    use std::env;
+    use std::fs;
+    use std::process;
+
+    #[test]
+    fn saves_answers_without_duplicates() {
+        let dir = env::temp_dir().join(format!("arsk-history-{}", process::id()));
+        let history = History::at(dir.join("hosts"));
+        for entry in &["web1", "db1", "web1", ""] {
+            history.save(entry).unwrap();
+        }
+        assert_eq!(history.load(), vec!["db1", "web1"]);
+        for i in 0..MAX_ENTRIES {
+            history.save(&i.to_string()).unwrap();
+        }
+        let entries = history.load();
+        assert_eq!(entries.len(), MAX_ENTRIES);
+        assert_eq!(entries[0], "0");
+        fs::remove_dir_all(dir).unwrap();
+    }
+
+    #[test]
+    fn keeps_keys_inside_the_history_directory() {
+        assert_eq!(file_name("deploy/../host name"), "deploy____host_name");
+    }
+}
@@ -0,0 +1,105 @@
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Key {
+    Char(char),
+    Ctrl(char),
+    Enter,
+    Tab,
+    Backspace,
+    Delete,
+    Escape,
+    Up,
+    Down,
+    Left,
+    Right,
+    WordLeft,
+    WordRight,
+    Home,
+    End,
+    Unknown,
+}
+
+/// Decodes the key at the start of `bytes`, returning it with the number of bytes
+/// it took up, or `None` if more bytes are needed.
+pub(crate) fn parse_key(bytes: &[u8]) -> Option<(Key, usize)> {
+    match *bytes.first()? {
+        0x1b => Some(parse_escape(bytes)),
+        b'\r' | b'\n' => Some((Key::Enter, 1)),
+        b'\t' => Some((Key::Tab, 1)),
+        0x7f | 0x08 => Some((Key::Backspace, 1)),
+        b @ 0x01..=0x1a => Some((Key::Ctrl((b'a' + b - 1) as char), 1)),
+        b if b < 0x20 => Some((Key::Unknown, 1)),
+        b => {
+            let width = match b {
+                0x00..=0x7f => 1,
+                0xc0..=0xdf => 2,
+                0xe0..=0xef => 3,
+                _ => 4,
+            };
+            if bytes.len() < width {
+                return None;
+            }
+            match ::std::str::from_utf8(&bytes[..width]) {
+                Ok(s) => s.chars().next().map(|c| (Key::Char(c), width)),
+                Err(_) => Some((Key::Unknown, 1)),
+            }
+        }
+    }
+}
+
+fn parse_escape(bytes: &[u8]) -> (Key, usize) {
+    match bytes.get(1) {
+        None => (Key::Escape, 1),
+        Some(&b'[') | Some(&b'O') => {
+            let end = bytes[2..].iter().position(|b| (0x40..=0x7e).contains(b));
+            let end = match end {
+                Some(end) => end + 2,
+                None => return (Key::Unknown, bytes.len()),
+            };
+            let params = &bytes[2..end];
+            let modified = params.ends_with(b";5") || params.ends_with(b";3");
+            let key = match (bytes[end], params) {
+                (b'A', _) => Key::Up,
+                (b'B', _) => Key::Down,
+                (b'C', _) if modified => Key::WordRight,
+                (b'D', _) if modified => Key::WordLeft,
+                (b'C', _) => Key::Right,
+                (b'D', _) => Key::Left,
+                (b'H', _) | (b'~', b"1") | (b'~', b"7") => Key::Home,
+                (b'F', _) | (b'~', b"4") | (b'~', b"8") => Key::End,
+                (b'~', b"3") => Key::Delete,
+                _ => Key::Unknown,
+            };
+            (key, end + 1)
+        }
+        Some(&b'b') => (Key::WordLeft, 2),
+        Some(&b'f') => (Key::WordRight, 2),
+        Some(_) => (Key::Unknown, 2),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{parse_key, Key};
+
+    #[test]
+    fn parses_plain_keys() {
+        assert_eq!(parse_key(b"j"), Some((Key::Char('j'), 1)));
+        assert_eq!(parse_key(b"\r"), Some((Key::Enter, 1)));
+        assert_eq!(parse_key(b"\x03"), Some((Key::Ctrl('c'), 1)));
+        assert_eq!(parse_key(b"\x7f"), Some((Key::Backspace, 1)));
+        assert_eq!(parse_key("é".as_bytes()), Some((Key::Char('é'), 2)));
+        assert_eq!(parse_key(&"é".as_bytes()[..1]), None);
+        assert_eq!(parse_key(b""), None);
+    }
+
+    #[test]
+    fn parses_escape_sequences() {
+        assert_eq!(parse_key(b"\x1b[A"), Some((Key::Up, 3)));
+        assert_eq!(parse_key(b"\x1bOB"), Some((Key::Down, 3)));
+        assert_eq!(parse_key(b"\x1b[3~x"), Some((Key::Delete, 4)));
+        assert_eq!(parse_key(b"\x1b[1~"), Some((Key::Home, 4)));
+        assert_eq!(parse_key(b"\x1b"), Some((Key::Escape, 1)));
+        assert_eq!(parse_key(b"\x1b[1;5D"), Some((Key::WordLeft, 6)));
+        assert_eq!(parse_key(b"\x1bf"), Some((Key::WordRight, 2)));
+    }
+}
@@ -1,48 +1,129 @@
+#[cfg(feature = "derive")]
+extern crate arsk_derive;
 #[macro_use]
-extern crate error_chain;
+extern crate serde_derive;
+extern crate dirs;
+extern crate libc;
+extern crate regex;
 extern crate rpassword;
-extern crate term_painter;
+extern crate serde;
+extern crate serde_json;
+extern crate toml;
 
-mod errors {
-    error_chain!{
-        foreign_links {
-            IoError(::std::io::Error);
-        }
-    }
-}
+mod ask;
+mod complete;
+mod editor;
+mod errors;
+mod form;
+mod hint;
+mod history;
+mod keys;
+mod presets;
+mod select;
+mod style;
+mod terminal;
+pub mod testing;
+mod theme;
+mod transform;
+mod validate;
+pub mod validators;
+mod yes_no;
 
-use errors::*;
-use std::io::{stdin, BufRead, Cursor, Write};
-use std::fmt::Display;
-use rpassword::{read_password, read_password_with_reader};
-use term_painter::Color::*;
-use term_painter::ToStyle;
+#[cfg(feature = "derive")]
+pub use arsk_derive::Ask;
+pub use ask::{Ask, Field};
+pub use complete::{Completer, PathCompleter, WordCompleter};
+pub use errors::{Error, Result};
+pub use form::{Answers, Form, Question, Value};
+pub use hint::{Hinter, HistoryHinter};
+pub use keys::Key;
+pub use presets::{set_presets, Presets};
+pub use style::{Colour, ColourChoice, ColourSupport, Style};
+pub use terminal::{MemoryTerminal, StdTerminal, Terminal};
+pub use theme::{set_theme, Theme};
+pub use transform::Transformer;
+pub use validate::Validator;
+
+use editor::LineEditor;
+use history::History;
+use presets::Preset;
+use std::error::Error as StdError;
+use std::fmt::{self, Display};
+use std::io::{BufRead, ErrorKind as IoErrorKind, Write};
+use std::str::FromStr;
+use terminal::{Console, RawMode};
+use transform::{CollapseWhitespace, Lowercase, Trim};
+use yes_no::parse_yes_no;
 
 pub type Answer = String;
 
-pub enum Colour {
-    Red,
-    Green,
-    Blue,
+/// Why an answer was turned down.
+enum Rejection {
+    /// The validator or the prompt itself rejected it, for this reason.
+    Invalid(String),
+    /// It could not be parsed into the type asked for.
+    Unparsable(Box<dyn StdError + Send + Sync>),
+}
+
+impl Rejection {
+    fn into_error(self, attempts: usize) -> Error {
+        match self {
+            Rejection::Invalid(reason) => Error::ValidationExhausted { attempts, reason },
+            Rejection::Unparsable(err) => Error::Parse(err),
+        }
+    }
+}
+
+impl From<String> for Rejection {
+    fn from(reason: String) -> Rejection {
+        Rejection::Invalid(reason)
+    }
 }
 
-struct Colours {
-    fg: term_painter::Color,
-    bg: term_painter::Color,
+impl fmt::Display for Rejection {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match *self {
+            Rejection::Invalid(ref reason) => write!(f, "{}", reason),
+            Rejection::Unparsable(ref err) => write!(f, "{}", err),
+        }
+    }
 }
 
 #[derive(Default)]
 struct State<'ask> {
     no_answer: Option<bool>,
     no_echo: Option<bool>,
+    line_editor: Option<bool>,
+    completer: Option<&'ask dyn Completer>,
+    hinter: Option<&'ask dyn Hinter>,
     confirm: Option<bool>,
+    exhausted: Option<bool>,
+    max_attempts: Option<usize>,
     default: Option<&'ask str>,
+    hint: Option<String>,
+    history: Option<&'ask str>,
+    key: Option<&'ask str>,
+    presets: Option<&'ask Presets>,
     prompt: Option<&'ask char>,
     bg_colour: Option<Colour>,
     fg_colour: Option<Colour>,
-    validate: Option<&'ask Fn(Answer) -> bool>,
-    redirect_in: Option<Cursor<&'ask [u8]>>,
-    redirect_out: Option<&'ask mut Write>,
+    error_colour: Option<Colour>,
+    hint_colour: Option<Colour>,
+    message_style: Option<Style>,
+    prompt_style: Option<Style>,
+    default_style: Option<Style>,
+    input_style: Option<Style>,
+    error_style: Option<Style>,
+    theme: Option<&'ask Theme>,
+    transformers: Vec<&'ask dyn Transformer>,
+    validate: Option<&'ask dyn Validator>,
+    validation_message: Option<&'ask str>,
+    yes_tokens: Option<&'ask [&'ask str]>,
+    no_tokens: Option<&'ask [&'ask str]>,
+    preselect: Option<&'ask [usize]>,
+    min_selections: Option<usize>,
+    max_selections: Option<usize>,
+    console: Console<'ask>,
 }
 
 #[derive(Default)]
@@ -52,34 +133,61 @@ pub struct StateBuilder<'ask, T: Display + Default> {
 }
 
 impl<'ask, T: Display + Default> StateBuilder<'ask, T> {
-    fn print_message(&mut self, colours: &Colours) -> Result<()> {
-        let message = match self.state.prompt {
-            Some(prompt) => format!("{}{}", self.msg, prompt),
-            None => format!("{}", self.msg),
+    fn print_message(&mut self, style: &Style) -> Result<()> {
+        let default = match (&self.state.hint, self.state.default, self.state.no_echo) {
+            (Some(hint), _, _) => format!(" [{}]", hint),
+            (None, Some(_), Some(true)) => " [********]".to_string(),
+            (None, Some(default), _) => format!(" [{}]", default),
+            _ => String::new(),
         };
-        self.print(colours, message)
+        let theme = self.current_theme();
+        let mut parts = vec![(self.msg.to_string(), *style)];
+        if !default.is_empty() {
+            let default_style = self.state.default_style.or(theme.default);
+            parts.push((default, default_style.unwrap_or(*style)));
+        }
+        if let Some(prompt) = self.state.prompt.cloned().or(theme.prompt_symbol) {
+            let prompt_style = self.state.prompt_style.or(theme.prompt);
+            parts.push((prompt.to_string(), prompt_style.unwrap_or(*style)));
+        }
+        self.print_parts(parts)
     }
 
-    fn print<D: Display>(&mut self, colours: &Colours, msg: D) -> Result<()> {
+    /// Prints each part in its own style as one line, running together
+    /// neighbouring parts that share a style.
+    fn print_parts(&mut self, parts: Vec<(String, Style)>) -> Result<()> {
+        let mut merged: Vec<(String, Style)> = Vec::new();
+        for (text, style) in parts {
+            match merged.last_mut() {
+                Some(last) if last.1 == style => last.0.push_str(&text),
+                _ => merged.push((text, style)),
+            }
+        }
+        let last = merged.pop().unwrap_or_default();
+        for (text, style) in merged {
+            self.state.console.write_styled(&text, &style)?;
+        }
+        Ok(self.state.console.write_line(&last.0, &last.1)?)
+    }
+
+    fn print<D: Display>(&mut self, style: &Style, msg: D) -> Result<()> {
         let output = format!("{}", msg);
-        match self.state.redirect_out {
-            Some(ref mut w) => w.write_all(output.as_bytes())?,
-            None => println!("{}", colours.fg.bg(colours.bg).paint(output)),
-        };
-        Ok(())
+        Ok(self.state.console.write_line(&output, style)?)
     }
 
-    fn print_confirm(&mut self, colours: &Colours) -> Result<()> {
-        self.print(colours, "Are you sure? Y/N")
+    fn print_confirm(&mut self, style: &Style) -> Result<()> {
+        self.print(style, "Are you sure? Y/N")
     }
 
     fn read_no_echo(&mut self) -> Result<Answer> {
         self.check_colour()?;
-        let response = match self.state.redirect_in {
-            Some(ref mut cur) => read_password_with_reader(Some(cur)),
-            _ => read_password(),
-        };
-        Ok(response.chain_err(|| "Unable to read input.")?)
+        match self.state.console.read_secret() {
+            Err(ref err) if err.kind() == IoErrorKind::UnexpectedEof => {
+                self.state.exhausted = Some(true);
+                Ok(String::new())
+            }
+            answer => Ok(answer?),
+        }
     }
 
     fn check_no_echo(&mut self) -> Result<Answer> {
@@ -92,72 +200,184 @@ impl<'ask, T: Display + Default> StateBuilder<'ask, T> {
         }
     }
 
-    fn get_fg_colour(&mut self) -> term_painter::Color {
-        match self.state.fg_colour {
-            Some(Colour::Red) => Red,
-            Some(Colour::Blue) => Blue,
-            Some(Colour::Green) => Green,
-            _ => White,
+    fn loop_confirm(&mut self, style: &Style) -> Result<()> {
+        loop {
+            self.print_message(style)?;
+            self.print_confirm(style)?;
+            let (yes, no) = self.tokens();
+            match parse_yes_no(yes, no, &self.read()?) {
+                Some(true) => return Ok(()),
+                Some(false) => return Err(Error::Declined),
+                None if self.state.exhausted == Some(true) => return Err(Error::Eof),
+                None => (),
+            };
         }
     }
 
-    fn get_bg_colour(&mut self) -> term_painter::Color {
-        match self.state.bg_colour {
-            Some(Colour::Red) => Red,
-            Some(Colour::Blue) => Blue,
-            Some(Colour::Green) => Green,
-            _ => Black,
+    fn check_confirm(&mut self, style: &Style) -> Result<()> {
+        match self.state.confirm {
+            Some(true) => self.loop_confirm(style),
+            _ => self.print_message(style),
         }
     }
 
-    fn loop_confirm(&mut self, colours: &Colours) -> Result<()> {
-        loop {
-            self.print_message(colours)?;
-            self.print_confirm(colours)?;
-            match self.read()?.to_string().trim() {
-              "y" | "Y" => break,
-              _ => (),
-            };
+    fn current_theme(&self) -> Theme {
+        match self.state.theme {
+            Some(theme) => theme.clone(),
+            None => theme::current(),
         }
-        Ok(())
     }
 
-    fn check_confirm(&mut self, colours: &Colours) -> Result<()> {
-        match self.state.confirm {
-            Some(true) => self.loop_confirm(colours),
-            _ => self.print_message(colours),
+    fn style(&mut self) -> Style {
+        let style = self
+            .state
+            .message_style
+            .unwrap_or(self.current_theme().message);
+        Style {
+            fg: self.state.fg_colour.or(style.fg),
+            bg: self.state.bg_colour.or(style.bg),
+            ..style
         }
     }
 
     fn check_colour(&mut self) -> Result<()> {
-        let colours = Colours {
-            fg: self.get_fg_colour(),
-            bg: self.get_bg_colour(),
+        let style = self.style();
+        self.check_confirm(&style)
+    }
+
+    fn print_error<D: Display>(&mut self, err: D) -> Result<()> {
+        let style = match self.state.error_style.or(self.current_theme().error) {
+            Some(style) => style,
+            None => self.style(),
+        };
+        let style = Style {
+            fg: self.state.error_colour.or(style.fg),
+            ..style
         };
-        self.check_confirm(&colours)
+        self.print(&style, err)
+    }
+
+    fn history_file(&self) -> Option<History> {
+        self.state.history.and_then(History::named)
+    }
+
+    /// Whether answers are read with the line editor rather than as plain lines.
+    fn uses_line_editor(&self) -> bool {
+        self.state.line_editor == Some(true) && self.state.console.is_tty()
+    }
+
+    /// Keeps an answer typed into the line editor for later prompts. Secret
+    /// answers and answers read from anywhere else are never kept.
+    fn remember(&self, answer: &str) {
+        if self.state.no_echo == Some(true) || !self.uses_line_editor() {
+            return;
+        }
+        match self.history_file() {
+            Some(history) => {
+                let _ = history.save(answer);
+            }
+            None => editor::remember(answer),
+        }
+    }
+
+    fn read_line(&mut self) -> Result<Answer> {
+        match self.uses_line_editor() {
+            true => {
+                let history = match self.history_file() {
+                    Some(history) => history.load(),
+                    None => editor::history(),
+                };
+                let editor = LineEditor::new(history, self.state.completer, self.state.hinter);
+                let theme = self.current_theme();
+                let style = self.state.input_style.unwrap_or(theme.input);
+                let hint_style = match self.state.hint_colour {
+                    Some(colour) => Style {
+                        fg: Some(colour),
+                        dim: false,
+                        ..theme.hint
+                    },
+                    None => theme.hint,
+                };
+                let mut raw = RawMode::enable(&mut self.state.console)?;
+                editor::read_line(&mut *raw, editor, &style, &hint_style)
+            }
+            false => {
+                let style = self.state.input_style.unwrap_or(self.current_theme().input);
+                let style = self.state.console.raw_style(&style);
+                if let Some(style) = style {
+                    self.state.console.write(&style.prefix())?;
+                }
+                let answer = self.state.console.read_line();
+                if style.is_some() {
+                    self.state.console.write("\x1b[0m")?;
+                }
+                Ok(answer?)
+            }
+        }
     }
 
     fn read(&mut self) -> Result<Answer> {
-        let mut buf = String::new();
-        match self.state.redirect_in {
-            Some(ref mut cur) => cur.read_line(&mut buf)
-                .chain_err(|| "Unable to read from input.")?,
-            None => stdin()
-                .read_line(&mut buf)
-                .chain_err(|| "Unable to read from STDIN.")?,
+        let answer = self.read_line()?;
+        if answer.is_empty() {
+            self.state.exhausted = Some(true);
+        }
+        Ok(strip_line_ending(answer))
+    }
+
+    fn apply_transformers(&self, answer: Answer) -> Answer {
+        self.state
+            .transformers
+            .iter()
+            .fold(answer, |answer, transformer| transformer.transform(&answer))
+    }
+
+    fn check_validation(&self, answer: Answer) -> ::std::result::Result<Answer, String> {
+        let reason = match self.state.validate {
+            Some(validator) => match validator.validate(&answer) {
+                Ok(()) => return Ok(answer),
+                Err(reason) => reason,
+            },
+            None => return Ok(answer),
         };
-        Ok(buf)
+        Err(self.state.validation_message.map_or(reason, str::to_string))
     }
 
-    fn check_validation(&self, answer: Answer) -> Result<Answer> {
-        if let Some(f) = self.state.validate {
-            if f(answer.clone()) {
-                Ok(answer)
-            } else {
-                Err("Response failed validation".into())
-            }
-        } else {
-            Ok(answer)
+    fn check_attempts(&self, attempts: usize) -> bool {
+        match self.state.max_attempts {
+            Some(max) => attempts >= max,
+            None => false,
+        }
+    }
+
+    /// Validates and then parses `answer`.
+    fn accept<V, R, F>(&self, answer: Answer, parse: &F) -> ::std::result::Result<V, Rejection>
+    where
+        R: Into<Rejection>,
+        F: Fn(Answer) -> ::std::result::Result<V, R>,
+    {
+        let answer = self.check_validation(answer).map_err(Rejection::Invalid)?;
+        parse(answer).map_err(Into::into)
+    }
+
+    fn fall_back<V, R, F>(&self, attempts: usize, rejection: Rejection, parse: &F) -> Result<V>
+    where
+        R: Into<Rejection>,
+        F: Fn(Answer) -> ::std::result::Result<V, R>,
+    {
+        let rejection = match self.state.default {
+            Some(default) => match self.accept(default.to_string(), parse) {
+                Ok(value) => return Ok(value),
+                Err(rejection) => rejection,
+            },
+            None => rejection,
+        };
+        Err(rejection.into_error(attempts))
+    }
+
+    fn check_default(&self, answer: Answer) -> Answer {
+        match self.state.default {
+            Some(default) if answer.is_empty() => default.to_string(),
+            _ => answer,
         }
     }
 
@@ -168,80 +388,345 @@ impl<'ask, T: Display + Default> StateBuilder<'ask, T> {
         }
     }
 
+    fn with_presets<R, F: FnOnce(&Presets) -> R>(&self, f: F) -> Option<R> {
+        match self.state.presets {
+            Some(presets) => Some(f(presets)),
+            None => presets::current().map(|presets| f(&presets)),
+        }
+    }
+
+    /// Whether the answer comes from presets rather than from asking.
+    fn is_unattended(&self) -> bool {
+        let key = self.state.key;
+        self.with_presets(|presets| {
+            presets.is_non_interactive() || key.and_then(|key| presets.get(key)).is_some()
+        })
+        .unwrap_or(false)
+    }
+
+    /// Answers from presets without asking, when they supply an answer or do not
+    /// allow asking. A supplied answer is still transformed, defaulted when empty
+    /// and validated, as a typed one would be.
+    fn preset_answer<V, R, F>(&self, presets: &Presets, parse: &F) -> Option<Result<V>>
+    where
+        R: Into<Rejection>,
+        F: Fn(Answer) -> ::std::result::Result<V, R>,
+    {
+        let supplied = self
+            .state
+            .key
+            .and_then(|key| presets.get(key))
+            .map(|preset| match preset {
+                Preset::Text(answer) => answer,
+                Preset::Bool(value) => self.bool_answer(value),
+            });
+        match (supplied, self.state.default) {
+            (Some(answer), _) => Some(
+                self.accept(self.check_default(self.apply_transformers(answer)), parse)
+                    .map_err(|rejection| rejection.into_error(1)),
+            ),
+            (None, _) if !presets.is_non_interactive() => None,
+            (None, Some(default)) => Some(
+                self.accept(default.to_string(), parse)
+                    .map_err(|rejection| rejection.into_error(0)),
+            ),
+            (None, None) => Some(Err(Error::Missing(
+                self.state
+                    .key
+                    .map_or_else(|| self.msg.to_string(), str::to_string),
+            ))),
+        }
+    }
+
+    fn answer<V, R, F>(&mut self, parse: F) -> Result<V>
+    where
+        R: Into<Rejection>,
+        F: Fn(Answer) -> ::std::result::Result<V, R>,
+    {
+        if let Some(answer) = self
+            .with_presets(|presets| self.preset_answer(presets, &parse))
+            .and_then(|answer| answer)
+        {
+            return answer;
+        }
+        let mut attempts = 0;
+        loop {
+            let typed = self.check_no_echo()?;
+            if self.state.exhausted == Some(true) && self.state.default.is_none() {
+                return Err(Error::Eof);
+            }
+            let answer = self.check_default(self.apply_transformers(typed.clone()));
+            attempts += 1;
+            let rejection = match self.accept(answer, &parse) {
+                Ok(value) => {
+                    self.remember(&typed);
+                    return Ok(value);
+                }
+                Err(rejection) => rejection,
+            };
+            if self.state.exhausted == Some(true) {
+                return self.fall_back(attempts, rejection, &parse);
+            }
+            self.print_error(&rejection)?;
+            if self.check_attempts(attempts) {
+                return self.fall_back(attempts, rejection, &parse);
+            }
+        }
+    }
+
     pub fn ask(&mut self) -> Result<Answer> {
-        let answer = self.check_no_echo()?;
-        let validated_answer = self.check_validation(answer)?;
-        self.discard_answer(validated_answer)
+        let answer = self.answer(Ok::<Answer, String>)?;
+        self.discard_answer(answer)
+    }
+
+    /// Parses the answer as a `V`, asking again until it does. If it never
+    /// does, the error from the last attempt is kept as `Error::Parse`.
+    pub fn ask_as<V>(&mut self) -> Result<V>
+    where
+        V: FromStr,
+        V::Err: Into<Box<dyn StdError + Send + Sync>>,
+    {
+        self.parse_with(|answer| answer.parse::<V>())
     }
 
-    pub fn no_echo(&mut self) -> &'ask mut StateBuilder<T> {
+    /// Parses the answer with `parse`, asking again until it succeeds. Answers
+    /// are trimmed first, so the validator sees what the parser sees.
+    pub fn parse_with<V, E, F>(&mut self, parse: F) -> Result<V>
+    where
+        E: Into<Box<dyn StdError + Send + Sync>>,
+        F: Fn(&str) -> ::std::result::Result<V, E>,
+    {
+        self.trim();
+        self.answer(|answer| parse(&answer).map_err(|err| Rejection::Unparsable(err.into())))
+    }
+
+    pub fn no_echo(&mut self) -> &mut StateBuilder<'ask, T> {
         self.state.no_echo = Some(true);
         self
     }
 
-    pub fn no_answer(&mut self) -> &'ask mut StateBuilder<T> {
+    pub fn line_editor(&mut self) -> &mut StateBuilder<'ask, T> {
+        self.state.line_editor = Some(true);
+        self
+    }
+
+    /// Saves answers typed into the line editor under `key` in the user's data
+    /// directory so they can be recalled with up and down, turning on the line
+    /// editor. Secret answers are never saved.
+    pub fn history(&mut self, key: &'ask str) -> &mut StateBuilder<'ask, T> {
+        self.state.history = Some(key);
+        self.line_editor()
+    }
+
+    /// Completes the answer with Tab, turning on the line editor.
+    pub fn completer(&mut self, completer: &'ask dyn Completer) -> &mut StateBuilder<'ask, T> {
+        self.state.completer = Some(completer);
+        self.line_editor()
+    }
+
+    /// Shows suggestions from `hinter` after the cursor, turning on the line editor.
+    pub fn hinter(&mut self, hinter: &'ask dyn Hinter) -> &mut StateBuilder<'ask, T> {
+        self.state.hinter = Some(hinter);
+        self.line_editor()
+    }
+
+    pub fn no_answer(&mut self) -> &mut StateBuilder<'ask, T> {
         self.state.no_answer = Some(true);
         self
     }
 
-    pub fn confirm(&mut self) -> &'ask mut StateBuilder<T> {
+    pub fn confirm(&mut self) -> &mut StateBuilder<'ask, T> {
         self.state.confirm = Some(true);
         self
     }
 
-    pub fn default(&mut self, msg: &'ask str) -> &'ask mut StateBuilder<T> {
+    pub fn default(&mut self, msg: &'ask str) -> &mut StateBuilder<'ask, T> {
         self.state.default = Some(msg);
         self
     }
 
-    pub fn prompt(&mut self, prompt: &'ask char) -> &'ask mut StateBuilder<T> {
+    pub fn prompt(&mut self, prompt: &'ask char) -> &mut StateBuilder<'ask, T> {
         self.state.prompt = Some(prompt);
         self
     }
 
-    pub fn bg_colour(&mut self, colour: Colour) -> &'ask mut StateBuilder<T> {
+    pub fn bg_colour(&mut self, colour: Colour) -> &mut StateBuilder<'ask, T> {
         self.state.bg_colour = Some(colour);
         self
     }
 
-    pub fn fg_colour(&mut self, colour: Colour) -> &'ask mut StateBuilder<T> {
+    pub fn fg_colour(&mut self, colour: Colour) -> &mut StateBuilder<'ask, T> {
         self.state.fg_colour = Some(colour);
         self
     }
 
-    pub fn validate(&mut self, f: &'ask (Fn(Answer) -> bool)) -> &'ask mut StateBuilder<T> {
-        self.state.validate = Some(f);
+    /// Styles the message, and the prompt and default unless they have their own
+    /// styles. `fg_colour` and `bg_colour` take precedence over its colours.
+    pub fn message_style(&mut self, style: Style) -> &mut StateBuilder<'ask, T> {
+        self.state.message_style = Some(style);
+        self
+    }
+
+    pub fn prompt_style(&mut self, style: Style) -> &mut StateBuilder<'ask, T> {
+        self.state.prompt_style = Some(style);
+        self
+    }
+
+    pub fn default_style(&mut self, style: Style) -> &mut StateBuilder<'ask, T> {
+        self.state.default_style = Some(style);
+        self
+    }
+
+    /// Styles the answer as it is typed.
+    pub fn input_style(&mut self, style: Style) -> &mut StateBuilder<'ask, T> {
+        self.state.input_style = Some(style);
+        self
+    }
+
+    pub fn error_style(&mut self, style: Style) -> &mut StateBuilder<'ask, T> {
+        self.state.error_style = Some(style);
+        self
+    }
+
+    /// Styles every part of the prompt not styled on the prompt itself, instead
+    /// of the theme given to `set_theme`.
+    pub fn theme(&mut self, theme: &'ask Theme) -> &mut StateBuilder<'ask, T> {
+        self.state.theme = Some(theme);
+        self
+    }
+
+    /// Whether to draw styles, by default only when they will reach a terminal.
+    /// `ColourChoice::Never` wins over any `colour_support`.
+    pub fn colour(&mut self, choice: ColourChoice) -> &mut StateBuilder<'ask, T> {
+        self.state.console.choose_colour(choice);
+        self
+    }
+
+    /// Draws styles as a terminal with `colours` would, instead of detecting what
+    /// stdout supports.
+    pub fn colour_support(&mut self, colours: ColourSupport) -> &mut StateBuilder<'ask, T> {
+        self.state.console.colours(colours);
+        self
+    }
+
+    /// Rewrites answers with `transformer` before they are validated, after any
+    /// transformers added before it.
+    pub fn transform(&mut self, transformer: &'ask dyn Transformer) -> &mut StateBuilder<'ask, T> {
+        self.state.transformers.push(transformer);
+        self
+    }
+
+    /// Removes whitespace from both ends of answers.
+    pub fn trim(&mut self) -> &mut StateBuilder<'ask, T> {
+        self.transform(&Trim)
+    }
+
+    pub fn lowercase(&mut self) -> &mut StateBuilder<'ask, T> {
+        self.transform(&Lowercase)
+    }
+
+    /// Turns each run of whitespace in answers into a single space.
+    pub fn collapse_whitespace(&mut self) -> &mut StateBuilder<'ask, T> {
+        self.transform(&CollapseWhitespace)
+    }
+
+    /// Names the question, so that `Presets` can answer it.
+    pub fn key(&mut self, key: &'ask str) -> &mut StateBuilder<'ask, T> {
+        self.state.key = Some(key);
+        self
+    }
+
+    /// Takes supplied answers from `presets` instead of those given to
+    /// `set_presets`.
+    pub fn presets(&mut self, presets: &'ask Presets) -> &mut StateBuilder<'ask, T> {
+        self.state.presets = Some(presets);
         self
     }
 
-    pub fn redirect_in(&mut self, cur: Cursor<&'ask [u8]>) -> &'ask mut StateBuilder<T> {
-        self.state.redirect_in = Some(cur);
+    /// Asks again while `validator` rejects the answer, showing why.
+    pub fn validate(&mut self, validator: &'ask dyn Validator) -> &mut StateBuilder<'ask, T> {
+        self.state.validate = Some(validator);
         self
     }
 
-    pub fn redirect_out<W: Write>(&mut self, w: &'ask mut W) -> &'ask mut StateBuilder<T> {
-        self.state.redirect_out = Some(w);
+    /// Shown instead of the validator's own message when an answer is rejected.
+    pub fn validation_message(&mut self, msg: &'ask str) -> &mut StateBuilder<'ask, T> {
+        self.state.validation_message = Some(msg);
         self
     }
+
+    pub fn max_attempts(&mut self, attempts: usize) -> &mut StateBuilder<'ask, T> {
+        self.state.max_attempts = Some(attempts);
+        self
+    }
+
+    pub fn error_colour(&mut self, colour: Colour) -> &mut StateBuilder<'ask, T> {
+        self.state.error_colour = Some(colour);
+        self
+    }
+
+    /// Colours hints instead of dimming them.
+    pub fn hint_colour(&mut self, colour: Colour) -> &mut StateBuilder<'ask, T> {
+        self.state.hint_colour = Some(colour);
+        self
+    }
+
+    pub fn redirect_in<R: BufRead + 'ask>(&mut self, r: R) -> &mut StateBuilder<'ask, T> {
+        self.state.console.input = Some(Box::new(r));
+        self
+    }
+
+    pub fn redirect_out<W: Write>(&mut self, w: &'ask mut W) -> &mut StateBuilder<'ask, T> {
+        self.state.console.output = Some(w);
+        self
+    }
+
+    pub fn terminal<U: Terminal>(&mut self, t: &'ask mut U) -> &mut StateBuilder<'ask, T> {
+        self.state.console.terminal = Some(t);
+        self
+    }
+}
+
+fn strip_line_ending(mut answer: Answer) -> Answer {
+    if answer.ends_with('\n') {
+        answer.pop();
+        if answer.ends_with('\r') {
+            answer.pop();
+        }
+    }
+    answer
 }
 
 pub fn input<'ask, T: Display + Default>(msg: T) -> StateBuilder<'ask, T> {
     StateBuilder {
-        msg: msg,
+        msg,
         ..Default::default()
     }
 }
 
 #[cfg(test)]
 mod tests {
+    use history;
+    use std::env;
     use std::fmt;
-    use {input, Answer, Colour, Cursor};
+    use std::fs;
+    use std::io::{BufRead, BufReader, Cursor};
+    use std::num::ParseIntError;
+    use std::process;
+    use testing::Script;
+    use validators::{InRange, NonEmpty, OneOf};
+    use {
+        input, Colour, ColourChoice, ColourSupport, Error, MemoryTerminal, Presets, Style, Theme,
+        Validator,
+    };
 
     const MSG: &str = "A test message.";
     const DEFAULT_RESPONSE: &str = "A response.";
     const EMPTY_RESPONSE: &str = "";
 
+    #[derive(Default)]
     enum Message {
+        #[default]
         TestMessage,
     }
 
@@ -251,14 +736,8 @@ mod tests {
         }
     }
 
-    impl Default for Message {
-        fn default() -> Message {
-            Message::TestMessage
-        }
-    }
-
     fn mock_input() -> Cursor<&'static [u8]> {
-        Cursor::new(&b"A response."[..])
+        Cursor::new(&b"A response.\n"[..])
     }
 
     fn mock_no_echo() -> Cursor<&'static [u8]> {
@@ -266,7 +745,25 @@ mod tests {
     }
 
     fn mock_confirm() -> Cursor<&'static [u8]> {
-        Cursor::new(&b"y"[..])
+        Cursor::new(&b"y\nA response.\n"[..])
+    }
+
+    fn mock_typed() -> Cursor<&'static [u8]> {
+        Cursor::new(&b"eighty\n8080\n"[..])
+    }
+
+    fn only(expected: &'static str) -> impl Fn(&str) -> Result<(), String> {
+        move |answer: &str| match answer {
+            _ if answer == expected => Ok(()),
+            _ => Err(format!("Try {}.", expected)),
+        }
+    }
+
+    fn is_port(answer: &str) -> Result<(), String> {
+        match answer.parse::<u16>() {
+            Ok(_) => Ok(()),
+            Err(_) => Err("Not a port.".to_string()),
+        }
     }
 
     #[test]
@@ -377,13 +874,7 @@ mod tests {
 
     #[test]
     fn can_validate_answer() {
-        let valid = |a: Answer| -> bool {
-            if a == DEFAULT_RESPONSE {
-                true
-            } else {
-                false
-            }
-        };
+        let valid = only(DEFAULT_RESPONSE);
         assert_eq!(
             input(MSG)
                 .redirect_in(mock_input())
@@ -402,4 +893,693 @@ mod tests {
             DEFAULT_RESPONSE
         );
     }
+
+    #[test]
+    fn can_ask_for_a_typed_answer() {
+        let mut sink = ::std::io::sink();
+        assert_eq!(
+            input(MSG)
+                .redirect_out(&mut sink)
+                .redirect_in(mock_typed())
+                .ask_as::<u16>()
+                .unwrap(),
+            8080
+        );
+    }
+
+    #[test]
+    fn can_parse_with_a_closure() {
+        let mut sink = ::std::io::sink();
+        let parse = |a: &str| match a {
+            "eighty" => Ok(80),
+            _ => Err("Not a number I know."),
+        };
+        assert_eq!(
+            input(MSG)
+                .redirect_out(&mut sink)
+                .redirect_in(mock_typed())
+                .parse_with(parse)
+                .unwrap(),
+            80
+        );
+    }
+
+    #[test]
+    fn keeps_the_last_parse_error() {
+        let mut sink = ::std::io::sink();
+        let err = input(MSG)
+            .max_attempts(1)
+            .redirect_out(&mut sink)
+            .redirect_in(mock_typed())
+            .ask_as::<u16>()
+            .unwrap_err();
+        match err {
+            Error::Parse(ref source) => assert!(source.is::<ParseIntError>()),
+            _ => panic!("unexpected error: {}", err),
+        }
+        let ports = OneOf::new(&["80"]);
+        let err = input(MSG)
+            .max_attempts(1)
+            .validate(&ports)
+            .redirect_out(&mut sink)
+            .redirect_in(mock_typed())
+            .ask_as::<u16>()
+            .unwrap_err();
+        assert!(matches!(err, Error::ValidationExhausted { .. }));
+    }
+
+    #[test]
+    fn can_validate_a_typed_answer() {
+        let mut sink = ::std::io::sink();
+        let valid = only("8080");
+        assert_eq!(
+            input(MSG)
+                .redirect_out(&mut sink)
+                .redirect_in(mock_typed())
+                .validate(&valid)
+                .ask_as::<u16>()
+                .unwrap(),
+            8080
+        );
+    }
+
+    #[test]
+    fn validates_the_answer_that_is_parsed() {
+        let mut sink = ::std::io::sink();
+        let ports = OneOf::new(&["80"]);
+        assert_eq!(
+            input(MSG)
+                .redirect_out(&mut sink)
+                .redirect_in(Cursor::new(&b" 80 \n"[..]))
+                .validate(&ports)
+                .ask_as::<u16>()
+                .unwrap(),
+            80
+        );
+    }
+
+    #[test]
+    fn stops_reprompting_at_end_of_input() {
+        let mut sink = ::std::io::sink();
+        let err = input(MSG)
+            .redirect_out(&mut sink)
+            .redirect_in(Cursor::new(&b"eighty\n"[..]))
+            .ask_as::<u16>()
+            .unwrap_err();
+        assert!(matches!(err, Error::Eof));
+    }
+
+    #[test]
+    fn fails_at_end_of_input_without_a_default() {
+        let mut sink = ::std::io::sink();
+        let err = input(MSG)
+            .redirect_out(&mut sink)
+            .redirect_in(Cursor::new(&b""[..]))
+            .ask()
+            .unwrap_err();
+        assert!(matches!(err, Error::Eof));
+        let err = input(MSG)
+            .no_echo()
+            .redirect_out(&mut sink)
+            .redirect_in(Cursor::new(&b""[..]))
+            .ask()
+            .unwrap_err();
+        assert!(matches!(err, Error::Eof));
+        let answer = input(MSG)
+            .default(DEFAULT_RESPONSE)
+            .redirect_out(&mut sink)
+            .redirect_in(Cursor::new(&b""[..]))
+            .ask()
+            .unwrap();
+        assert_eq!(answer, DEFAULT_RESPONSE);
+    }
+
+    #[test]
+    fn does_not_reject_the_end_of_input() {
+        let mut term = MemoryTerminal::new("");
+        let err = input("Deploy?")
+            .terminal(&mut term)
+            .ask_yes_no()
+            .unwrap_err();
+        assert!(matches!(err, Error::Eof));
+        let err = input("Env")
+            .terminal(&mut term)
+            .ask_select(&["staging", "prod"])
+            .unwrap_err();
+        assert!(matches!(err, Error::Eof));
+        assert_eq!(
+            term.output(),
+            "Deploy? [y/n]\n  1) staging\n  2) prod\nEnv\n"
+        );
+    }
+
+    #[test]
+    fn saves_history_only_for_answers_typed_into_the_line_editor() {
+        let dir = env::temp_dir().join(format!("arsk-prompt-history-{}", process::id()));
+        history::use_dir(&dir);
+        let mut term = MemoryTerminal::new("web1\r");
+        term.tty(true);
+        input("Host")
+            .history("hosts")
+            .terminal(&mut term)
+            .ask()
+            .unwrap();
+        let mut sink = ::std::io::sink();
+        input("Host")
+            .history("hosts")
+            .redirect_in(Cursor::new(&b"web2\n"[..]))
+            .redirect_out(&mut sink)
+            .ask()
+            .unwrap();
+        assert_eq!(fs::read_to_string(dir.join("hosts")).unwrap(), "web1\n");
+        fs::remove_dir_all(dir).unwrap();
+    }
+
+    #[test]
+    fn never_saves_secret_answers() {
+        let dir = env::temp_dir().join(format!("arsk-secret-history-{}", process::id()));
+        history::use_dir(&dir);
+        let mut term = MemoryTerminal::new("hunter2\n");
+        term.tty(true);
+        let answer = input("Password")
+            .no_echo()
+            .history("passwords")
+            .terminal(&mut term)
+            .ask()
+            .unwrap();
+        assert_eq!(answer, "hunter2");
+        assert!(!dir.join("passwords").exists());
+    }
+
+    #[test]
+    fn can_retry_failed_validation() {
+        let mut out = Vec::new();
+        let valid = only("8080");
+        let answer = input(MSG)
+            .redirect_out(&mut out)
+            .redirect_in(mock_typed())
+            .validate(&valid)
+            .ask()
+            .unwrap();
+        assert_eq!(answer, "8080");
+        assert_eq!(out, b"A test message.\nTry 8080.\nA test message.\n");
+    }
+
+    #[test]
+    fn can_validate_with_built_in_validators() {
+        let mut out = Vec::new();
+        let port = NonEmpty.and(InRange::new(1, 65535));
+        let answer = input("Port")
+            .redirect_out(&mut out)
+            .redirect_in(Cursor::new(&b"0\n8080\n"[..]))
+            .validate(&port)
+            .ask_as::<u16>()
+            .unwrap();
+        assert_eq!(answer, 8080);
+        assert_eq!(out, b"Port\nEnter a number from 1 to 65535.\nPort\n");
+    }
+
+    #[test]
+    fn can_replace_the_validation_message() {
+        let mut out = Vec::new();
+        let valid = only("8080");
+        input(MSG)
+            .redirect_out(&mut out)
+            .redirect_in(mock_typed())
+            .validate(&valid)
+            .validation_message("Not the port.")
+            .ask()
+            .unwrap();
+        assert_eq!(out, b"A test message.\nNot the port.\nA test message.\n");
+    }
+
+    #[test]
+    fn can_limit_validation_attempts() {
+        let mut sink = ::std::io::sink();
+        let valid = only("8080");
+        let err = input(MSG)
+            .redirect_out(&mut sink)
+            .redirect_in(mock_typed())
+            .validate(&valid)
+            .max_attempts(1)
+            .ask()
+            .unwrap_err();
+        match err {
+            Error::ValidationExhausted {
+                attempts: 1,
+                ref reason,
+            } => {
+                assert_eq!(reason, "Try 8080.")
+            }
+            _ => panic!("unexpected error: {}", err),
+        }
+    }
+
+    #[test]
+    fn can_fall_back_to_default_after_failed_attempts() {
+        let mut sink = ::std::io::sink();
+        let valid = only("80");
+        assert_eq!(
+            input(MSG)
+                .redirect_out(&mut sink)
+                .redirect_in(mock_typed())
+                .validate(&valid)
+                .error_colour(Colour::Red)
+                .max_attempts(2)
+                .default("80")
+                .ask_as::<u16>()
+                .unwrap(),
+            80
+        );
+    }
+
+    #[test]
+    fn can_answer_with_the_default() {
+        let mut out = Vec::new();
+        assert_eq!(
+            input("Port")
+                .redirect_out(&mut out)
+                .redirect_in(Cursor::new(&b"\n"[..]))
+                .prompt(&':')
+                .default("8080")
+                .ask()
+                .unwrap(),
+            "8080"
+        );
+        assert_eq!(out, b"Port [8080]:\n");
+    }
+
+    #[test]
+    fn can_answer_a_secret_with_the_default() {
+        let mut out = Vec::new();
+        assert_eq!(
+            input("Password")
+                .redirect_out(&mut out)
+                .redirect_in(Cursor::new(&b"\r\n"[..]))
+                .no_echo()
+                .default("hunter2")
+                .ask()
+                .unwrap(),
+            "hunter2"
+        );
+        assert_eq!(out, b"Password [********]\n");
+    }
+
+    #[test]
+    fn strips_line_endings_from_every_answer() {
+        let mut sink = ::std::io::sink();
+        let typed = input(MSG)
+            .redirect_out(&mut sink)
+            .redirect_in(Cursor::new(&b"web1\r\n"[..]))
+            .ask()
+            .unwrap();
+        let secret = input(MSG)
+            .redirect_out(&mut sink)
+            .redirect_in(Cursor::new(&b"web1\r\n"[..]))
+            .no_echo()
+            .ask()
+            .unwrap();
+        let mut term = MemoryTerminal::new("web1\r");
+        term.tty(true);
+        let edited = input(MSG).line_editor().terminal(&mut term).ask().unwrap();
+        assert_eq!((typed.as_str(), secret.as_str()), ("web1", "web1"));
+        assert_eq!(edited, "web1");
+    }
+
+    #[test]
+    fn can_transform_answers_before_validating_them() {
+        let mut out = Vec::new();
+        let env = OneOf::new(&["dev", "prod"]);
+        let answer = input("Env")
+            .redirect_out(&mut out)
+            .redirect_in(Cursor::new(&b"  Staging\n PROD \n"[..]))
+            .trim()
+            .lowercase()
+            .validate(&env)
+            .ask()
+            .unwrap();
+        assert_eq!(answer, "prod");
+        assert_eq!(out, b"Env\nChoose one of dev, prod.\nEnv\n");
+    }
+
+    #[test]
+    fn can_chain_custom_transformers() {
+        let slug = |answer: &str| answer.replace(' ', "-");
+        let answer = input(MSG)
+            .redirect_in(Cursor::new(&b"New   York\n"[..]))
+            .collapse_whitespace()
+            .transform(&slug)
+            .ask()
+            .unwrap();
+        assert_eq!(answer, "New-York");
+        let answer = input(MSG)
+            .redirect_in(Cursor::new(&b"   \n"[..]))
+            .trim()
+            .default("dev")
+            .ask()
+            .unwrap();
+        assert_eq!(answer, "dev");
+    }
+
+    #[test]
+    fn can_take_answers_from_presets() {
+        let mut out = Vec::new();
+        let mut presets = Presets::new();
+        presets.set("port", "80").set("user", "root");
+        let port = input("Port")
+            .key("port")
+            .presets(&presets)
+            .redirect_out(&mut out)
+            .redirect_in(Cursor::new(&b"8080\n"[..]))
+            .ask_as::<u16>()
+            .unwrap();
+        assert_eq!(port, 80);
+        assert!(out.is_empty());
+        let name = input("Name")
+            .key("name")
+            .presets(&presets)
+            .redirect_out(&mut out)
+            .redirect_in(Cursor::new(&b"web\n"[..]))
+            .ask()
+            .unwrap();
+        assert_eq!(name, "web");
+        let valid = only("admin");
+        let err = input("User")
+            .key("user")
+            .presets(&presets)
+            .validate(&valid)
+            .ask()
+            .unwrap_err();
+        match err {
+            Error::ValidationExhausted {
+                attempts: 1,
+                ref reason,
+            } => assert_eq!(reason, "Try admin."),
+            _ => panic!("unexpected error: {}", err),
+        }
+    }
+
+    #[test]
+    fn treats_preset_answers_as_typed_ones() {
+        let mut presets = Presets::from_toml("port = \"\"\ntls = true\ndebug = false").unwrap();
+        presets.set("name", "  ");
+        let port = input("Port")
+            .key("port")
+            .presets(&presets)
+            .default("8080")
+            .ask_as::<u16>()
+            .unwrap();
+        assert_eq!(port, 8080);
+        let name = input("Name")
+            .key("name")
+            .presets(&presets)
+            .trim()
+            .default("web")
+            .ask()
+            .unwrap();
+        assert_eq!(name, "web");
+        let tls = input("TLS?")
+            .key("tls")
+            .presets(&presets)
+            .yes_tokens(&["oui"])
+            .no_tokens(&["non"])
+            .ask_yes_no()
+            .unwrap();
+        assert!(tls);
+        let debug = input("Debug?")
+            .key("debug")
+            .presets(&presets)
+            .yes_tokens(&["oui"])
+            .no_tokens(&["non"])
+            .ask_yes_no()
+            .unwrap();
+        assert!(!debug);
+        let tls = input("TLS").key("tls").presets(&presets).ask().unwrap();
+        assert_eq!(tls, "yes");
+    }
+
+    #[test]
+    fn fails_without_asking_when_non_interactive() {
+        let mut presets = Presets::new();
+        presets.non_interactive(true);
+        let port = input("Port")
+            .key("port")
+            .presets(&presets)
+            .default("8080")
+            .ask_as::<u16>()
+            .unwrap();
+        assert_eq!(port, 8080);
+        let err = input("Port")
+            .key("port")
+            .presets(&presets)
+            .ask_as::<u16>()
+            .unwrap_err();
+        match err {
+            Error::Missing(ref key) => assert_eq!(key, "port"),
+            _ => panic!("unexpected error: {}", err),
+        }
+        let mut term = MemoryTerminal::new("");
+        term.tty(true);
+        let err = input("Region")
+            .presets(&presets)
+            .terminal(&mut term)
+            .ask_select(&["eu", "us"])
+            .unwrap_err();
+        assert!(matches!(err, Error::Missing(_)));
+        assert_eq!(term.output(), "");
+    }
+
+    #[test]
+    fn can_validate_the_default() {
+        let mut sink = ::std::io::sink();
+        let valid = |a: &str| match a {
+            "8080" => Err("Port 8080 is taken.".to_string()),
+            _ => Ok(()),
+        };
+        assert!(input(MSG)
+            .redirect_out(&mut sink)
+            .redirect_in(Cursor::new(&b"\n"[..]))
+            .validate(&valid)
+            .default("8080")
+            .ask()
+            .is_err());
+    }
+
+    #[test]
+    fn can_redirect_input_from_an_owned_reader() {
+        let reader = Cursor::new(b"A response.\n".to_vec());
+        assert_eq!(
+            input(MSG).redirect_in(reader).ask().unwrap(),
+            DEFAULT_RESPONSE
+        );
+    }
+
+    #[test]
+    fn can_share_a_borrowed_reader_between_questions() {
+        let mut sink = ::std::io::sink();
+        let mut reader = BufReader::new(&b"first\nsecond\n"[..]);
+        let first = input(MSG)
+            .redirect_out(&mut sink)
+            .redirect_in(&mut reader)
+            .ask()
+            .unwrap();
+        let second = input(MSG)
+            .redirect_out(&mut sink)
+            .redirect_in(&mut reader)
+            .no_echo()
+            .ask()
+            .unwrap();
+        assert_eq!((first.as_str(), second.as_str()), ("first", "second"));
+    }
+
+    #[test]
+    fn can_redirect_input_from_a_boxed_reader() {
+        let reader: Box<dyn BufRead> = Box::new(mock_input());
+        assert_eq!(
+            input(MSG).redirect_in(reader).ask().unwrap(),
+            DEFAULT_RESPONSE
+        );
+    }
+
+    #[test]
+    fn can_use_a_custom_terminal() {
+        let mut term = MemoryTerminal::new("A response.\n");
+        assert_eq!(
+            input(MSG).terminal(&mut term).ask().unwrap(),
+            DEFAULT_RESPONSE
+        );
+        assert_eq!(term.output(), "A test message.\n");
+    }
+
+    #[test]
+    fn can_decline_confirmation() {
+        let err = input(MSG)
+            .redirect_in(Cursor::new(&b"maybe\nn\n"[..]))
+            .confirm()
+            .ask()
+            .unwrap_err();
+        match err {
+            Error::Declined => (),
+            _ => panic!("unexpected error: {}", err),
+        }
+    }
+
+    #[test]
+    fn can_style_each_part_of_the_prompt() {
+        let mut script = Script::new();
+        script
+            .keep_ansi()
+            .expect("\x1b[1mPort\x1b[0m\x1b[2m [80]\x1b[0m\x1b[1m:\x1b[0m")
+            .send("x")
+            .expect(
+                "\x1b[1;38;5;208mNot a port.\x1b[0m\n\x1b[1mPort\x1b[0m\x1b[2m [80]\x1b[0m\x1b[1m:\x1b[0m",
+            )
+            .send("8080");
+        script.run(|term| {
+            input("Port")
+                .prompt(&':')
+                .default("80")
+                .message_style(Style::new().bold())
+                .default_style(Style::new().dim())
+                .error_style(Style::new().bold().fg(Colour::Fixed(208)))
+                .validate(&is_port)
+                .terminal(term)
+                .ask()
+                .unwrap()
+        });
+    }
+
+    #[test]
+    fn can_style_the_typed_answer() {
+        let mut term = MemoryTerminal::new("80\n");
+        input("Port")
+            .input_style(Style::new().fg(Colour::Cyan))
+            .terminal(&mut term)
+            .ask()
+            .unwrap();
+        assert_eq!(term.output(), "Port\n");
+
+        let mut term = MemoryTerminal::new("80\n");
+        input("Port")
+            .input_style(Style::new().fg(Colour::Cyan))
+            .colour_support(ColourSupport::Basic)
+            .terminal(&mut term)
+            .ask()
+            .unwrap();
+        assert_eq!(term.output(), "Port\n\x1b[36m\x1b[0m");
+    }
+
+    #[test]
+    fn can_style_a_prompt_with_a_theme() {
+        let theme = Theme {
+            message: Style::new().bold(),
+            prompt_symbol: Some('?'),
+            error: Some(Style::new().fg(Colour::Red)),
+            ..Theme::plain()
+        };
+        let mut script = Script::new();
+        script
+            .keep_ansi()
+            .expect("\x1b[1mPort [80]?\x1b[0m")
+            .send("x")
+            .expect("\x1b[31mNot a port.\x1b[0m\n\x1b[1mPort [80]?\x1b[0m")
+            .send("8080")
+            .expect("\x1b[1;33mPort\x1b[0m\x1b[1m [80]\x1b[0m\x1b[1;33m?\x1b[0m")
+            .send("");
+        script.run(|term| {
+            input("Port")
+                .default("80")
+                .theme(&theme)
+                .validate(&is_port)
+                .terminal(term)
+                .ask()
+                .unwrap();
+            input("Port")
+                .default("80")
+                .theme(&theme)
+                .fg_colour(Colour::Yellow)
+                .default_style(Style::new().bold())
+                .terminal(term)
+                .ask()
+                .unwrap();
+        });
+    }
+
+    #[test]
+    fn can_override_colour_support() {
+        let mut script = Script::new();
+        script
+            .keep_ansi()
+            .expect("\x1b[1;38;5;208mPort\x1b[0m")
+            .send("80")
+            .expect("Port")
+            .send("80");
+        script.run(|term| {
+            let orange = Style::new().fg(Colour::Rgb(255, 135, 0)).bold();
+            input("Port")
+                .message_style(orange)
+                .colour_support(ColourSupport::Ansi256)
+                .terminal(term)
+                .ask()
+                .unwrap();
+            input("Port")
+                .message_style(orange)
+                .colour_support(ColourSupport::Plain)
+                .terminal(term)
+                .ask()
+                .unwrap();
+        });
+    }
+
+    #[test]
+    fn writes_whole_lines_to_redirected_output() {
+        let mut output = Vec::new();
+        input("Port")
+            .prompt(&':')
+            .fg_colour(Colour::Red)
+            .redirect_in(Cursor::new(&b"x\n80\n"[..]))
+            .redirect_out(&mut output)
+            .validate(&is_port)
+            .ask()
+            .unwrap();
+        assert_eq!(
+            String::from_utf8(output).unwrap(),
+            "Port:\nNot a port.\nPort:\n"
+        );
+    }
+
+    #[test]
+    fn can_choose_when_to_colour() {
+        let mut output = Vec::new();
+        input("Port")
+            .fg_colour(Colour::Red)
+            .colour(ColourChoice::Always)
+            .redirect_in(mock_input())
+            .redirect_out(&mut output)
+            .ask()
+            .unwrap();
+        assert_eq!(output, b"\x1b[31mPort\x1b[0m\n");
+
+        let mut output = Vec::new();
+        input("Port")
+            .fg_colour(Colour::Red)
+            .colour(ColourChoice::Never)
+            .colour_support(ColourSupport::Basic)
+            .redirect_in(mock_input())
+            .redirect_out(&mut output)
+            .ask()
+            .unwrap();
+        assert_eq!(output, b"Port\n");
+
+        let mut script = Script::new();
+        script.keep_ansi().expect("Port").send("80");
+        script.run(|term| {
+            input("Port")
+                .fg_colour(Colour::Red)
+                .colour(ColourChoice::Never)
+                .terminal(term)
+                .ask()
+                .unwrap()
+        });
+    }
 }
@@ -0,0 +1,215 @@
+use serde_json::{self, Value as Json};
+use std::collections::HashMap;
+use std::env;
+use std::fs;
+use std::path::Path;
+use std::sync::Mutex;
+use toml;
+use {Error, Result};
+
+/// The presets used by prompts that are not given their own.
+static PRESETS: Mutex<Option<Presets>> = Mutex::new(None);
+
+/// Answers supplied ahead of time for prompts with a key, so they can run
+/// without anyone to ask.
+#[derive(Clone, Debug, Default)]
+pub struct Presets {
+    values: HashMap<String, String>,
+    env_prefix: Option<String>,
+    files: HashMap<String, Preset>,
+    non_interactive: bool,
+}
+
+/// One supplied answer. Booleans from files are kept as they are, to be
+/// answered with whichever yes/no tokens the prompt accepts.
+#[derive(Clone, Debug, PartialEq)]
+pub(crate) enum Preset {
+    Text(String),
+    Bool(bool),
+}
+
+impl From<HashMap<String, String>> for Presets {
+    fn from(values: HashMap<String, String>) -> Presets {
+        Presets {
+            values,
+            ..Presets::default()
+        }
+    }
+}
+
+impl Presets {
+    pub fn new() -> Presets {
+        Presets::default()
+    }
+
+    /// Reads answers from TOML. Tables nest keys with dots, so `port` in a
+    /// `[db]` table answers the key `db.port`.
+    pub fn from_toml(text: &str) -> Result<Presets> {
+        let answers = toml::from_str(text).map_err(|err| Error::Parse(Box::new(err)))?;
+        Ok(Presets::from_answers(&answers))
+    }
+
+    /// Reads answers from a JSON object, nesting keys as `from_toml` does.
+    pub fn from_json(text: &str) -> Result<Presets> {
+        let answers = serde_json::from_str(text).map_err(|err| Error::Parse(Box::new(err)))?;
+        Ok(Presets::from_answers(&answers))
+    }
+
+    /// Reads answers from a `.json` file, or from TOML otherwise.
+    pub fn load<P: AsRef<Path>>(path: P) -> Result<Presets> {
+        let path = path.as_ref();
+        let text = fs::read_to_string(path)?;
+        match path.extension().and_then(|ext| ext.to_str()) {
+            Some("json") => Presets::from_json(&text),
+            _ => Presets::from_toml(&text),
+        }
+    }
+
+    fn from_answers(answers: &Json) -> Presets {
+        let mut files = HashMap::new();
+        flatten("", answers, &mut files);
+        Presets {
+            files,
+            ..Presets::default()
+        }
+    }
+
+    /// Supplies `answer` for `key`, before any from the environment or a file.
+    pub fn set(&mut self, key: &str, answer: &str) -> &mut Presets {
+        self.values.insert(key.to_string(), answer.to_string());
+        self
+    }
+
+    /// Looks for answers in environment variables named by `prefix` and the key
+    /// in upper case, with anything but letters and digits as underscores. With
+    /// a prefix of `APP_`, `db.port` is answered by `APP_DB_PORT`.
+    pub fn env(&mut self, prefix: &str) -> &mut Presets {
+        self.env_prefix = Some(prefix.to_string());
+        self
+    }
+
+    /// Whether prompts must never ask, failing instead when they have no answer
+    /// or default.
+    pub fn non_interactive(&mut self, non_interactive: bool) -> &mut Presets {
+        self.non_interactive = non_interactive;
+        self
+    }
+
+    pub(crate) fn is_non_interactive(&self) -> bool {
+        self.non_interactive
+    }
+
+    pub(crate) fn get(&self, key: &str) -> Option<Preset> {
+        self.get_with(key, |name| env::var(name).ok())
+    }
+
+    fn get_with<F: Fn(&str) -> Option<String>>(&self, key: &str, var: F) -> Option<Preset> {
+        let from_env = || {
+            let prefix = self.env_prefix.as_ref()?;
+            var(&format!("{}{}", prefix, env_name(key)))
+        };
+        self.values
+            .get(key)
+            .cloned()
+            .or_else(from_env)
+            .map(Preset::Text)
+            .or_else(|| self.files.get(key).cloned())
+    }
+}
+
+fn env_name(key: &str) -> String {
+    key.chars()
+        .map(|c| match c.is_ascii_alphanumeric() {
+            true => c.to_ascii_uppercase(),
+            false => '_',
+        })
+        .collect()
+}
+
+/// Writes each answer in `value` as it would be typed, keyed by its path.
+fn flatten(key: &str, value: &Json, answers: &mut HashMap<String, Preset>) {
+    let answer = match *value {
+        Json::Object(ref table) => {
+            for (name, value) in table {
+                let path = match key {
+                    "" => name.clone(),
+                    _ => format!("{}.{}", key, name),
+                };
+                flatten(&path, value, answers);
+            }
+            return;
+        }
+        Json::Array(ref items) => {
+            Preset::Text(items.iter().map(typed).collect::<Vec<_>>().join(", "))
+        }
+        Json::Bool(value) => Preset::Bool(value),
+        _ => Preset::Text(typed(value)),
+    };
+    answers.insert(key.to_string(), answer);
+}
+
+fn typed(value: &Json) -> String {
+    match *value {
+        Json::String(ref text) => text.clone(),
+        Json::Bool(true) => "yes".to_string(),
+        Json::Bool(false) => "no".to_string(),
+        Json::Null => String::new(),
+        ref other => other.to_string(),
+    }
+}
+
+/// Sets the presets for every prompt not given its own.
+pub fn set_presets(presets: Presets) {
+    if let Ok(mut current) = PRESETS.lock() {
+        *current = Some(presets);
+    }
+}
+
+pub(crate) fn current() -> Option<Presets> {
+    PRESETS.lock().ok().and_then(|presets| presets.clone())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{Preset, Presets};
+
+    fn text(answer: &str) -> Option<Preset> {
+        Some(Preset::Text(answer.to_string()))
+    }
+
+    #[test]
+    fn reads_nested_answers_from_files() {
+        let presets = Presets::from_toml(
+            r#"
+            name = "web"
+            tls = true
+            hosts = ["db1", "db2"]
+
+            [db]
+            port = 5432
+            "#,
+        )
+        .unwrap();
+        assert_eq!(presets.get("name"), text("web"));
+        assert_eq!(presets.get("tls"), Some(Preset::Bool(true)));
+        assert_eq!(presets.get("hosts"), text("db1, db2"));
+        assert_eq!(presets.get("db.port"), text("5432"));
+        let presets = Presets::from_json(r#"{"db": {"port": 5432}}"#).unwrap();
+        assert_eq!(presets.get("db.port"), text("5432"));
+        assert!(Presets::from_toml("port = ").is_err());
+    }
+
+    #[test]
+    fn prefers_set_answers_then_the_environment() {
+        let mut presets = Presets::from_toml("port = 80\nname = \"web\"\nenv = \"dev\"").unwrap();
+        presets.env("APP_").set("port", "8080");
+        let env = |name: &str| match name {
+            "APP_PORT" | "APP_NAME" => Some("db".to_string()),
+            _ => None,
+        };
+        assert_eq!(presets.get_with("port", env), text("8080"));
+        assert_eq!(presets.get_with("name", env), text("db"));
+        assert_eq!(presets.get_with("env", env), text("dev"));
+        assert_eq!(presets.get_with("tls", env), None);
+    }
+}
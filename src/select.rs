@@ -0,0 +1,605 @@
+use keys::Key;
+use std::fmt::Display;
+use terminal::{RawMode, Terminal};
+use {Error, Result, StateBuilder, Theme, Validator};
+
+fn labels<V: Display>(options: &[V]) -> Vec<String> {
+    options.iter().map(|option| option.to_string()).collect()
+}
+
+/// Finds the option an answer refers to, either by its number or its label.
+pub(crate) fn parse_option(labels: &[String], answer: &str) -> Option<usize> {
+    let answer = answer.trim();
+    match answer.parse::<usize>() {
+        Ok(n) if n >= 1 && n <= labels.len() => Some(n - 1),
+        _ => labels
+            .iter()
+            .position(|label| label.to_lowercase() == answer.to_lowercase()),
+    }
+}
+
+fn parse_range(labels: &[String], token: &str) -> Option<Vec<usize>> {
+    let mut ends = token.splitn(2, '-').map(|n| n.trim().parse::<usize>());
+    match (ends.next(), ends.next()) {
+        (Some(Ok(from)), Some(Ok(to))) if from >= 1 && from <= to && to <= labels.len() => {
+            Some((from - 1..to).collect())
+        }
+        _ => None,
+    }
+}
+
+/// Parses a list of numbers, ranges and labels separated by commas or spaces.
+pub(crate) fn parse_selection(
+    labels: &[String],
+    answer: &str,
+) -> ::std::result::Result<Vec<usize>, String> {
+    let mut selection = Vec::new();
+    let tokens = answer
+        .split(|c: char| c == ',' || c.is_whitespace())
+        .filter(|token| !token.is_empty());
+    for token in tokens {
+        match parse_option(labels, token) {
+            Some(index) => selection.push(index),
+            None => match parse_range(labels, token) {
+                Some(range) => selection.extend(range),
+                None => return Err(format!("{} is not one of the options.", token)),
+            },
+        }
+    }
+    selection.sort();
+    selection.dedup();
+    Ok(selection)
+}
+
+/// Checks the label of the chosen option with the prompt's validator, if any.
+fn check_label(
+    validator: Option<&dyn Validator>,
+    message: Option<&str>,
+    label: &str,
+) -> ::std::result::Result<(), String> {
+    match validator {
+        Some(validator) => validator
+            .validate(label)
+            .map_err(|reason| message.map_or(reason, str::to_string)),
+        None => Ok(()),
+    }
+}
+
+fn check_selection(
+    labels: &[String],
+    answer: &str,
+    preselected: &[usize],
+    min: Option<usize>,
+    max: Option<usize>,
+    message: Option<&str>,
+) -> ::std::result::Result<Vec<usize>, String> {
+    let selection = match answer.trim().is_empty() {
+        true => preselected.to_vec(),
+        false => parse_selection(labels, answer)
+            .map_err(|reason| message.map_or(reason, |msg| msg.to_string()))?,
+    };
+    check_count(selection.len(), min, max).map(|_| selection)
+}
+
+fn check_count(
+    count: usize,
+    min: Option<usize>,
+    max: Option<usize>,
+) -> ::std::result::Result<(), String> {
+    match (min, max) {
+        (Some(min), _) if count < min => Err(format!("Please choose at least {} option(s).", min)),
+        (_, Some(max)) if count > max => Err(format!("Please choose at most {} option(s).", max)),
+        _ => Ok(()),
+    }
+}
+
+fn menu_lines(
+    theme: &Theme,
+    labels: &[String],
+    cursor: usize,
+    checked: Option<&[bool]>,
+    error: Option<&String>,
+) -> Vec<String> {
+    let mut lines: Vec<String> = labels
+        .iter()
+        .enumerate()
+        .map(|(i, label)| {
+            let pointer = match i == cursor {
+                true => &theme.selected,
+                false => &theme.unselected,
+            };
+            match checked {
+                Some(checked) if checked[i] => format!("{} {} {}", pointer, theme.checked, label),
+                Some(_) => format!("{} {} {}", pointer, theme.unchecked, label),
+                None => format!("{} {}", pointer, label),
+            }
+        })
+        .collect();
+    lines.extend(error.cloned());
+    lines
+}
+
+/// Moves back over the `drawn` lines of the previous frame and clears them.
+fn clear(terminal: &mut dyn Terminal, drawn: usize) -> ::std::io::Result<()> {
+    match drawn {
+        0 => Ok(()),
+        n => terminal.write(&format!("\x1b[{}A\r\x1b[J", n)),
+    }
+}
+
+impl<'ask, T: Display + Default> StateBuilder<'ask, T> {
+    /// Lets the user pick options with the arrow keys, redrawing the menu in place.
+    fn navigate<F>(
+        &mut self,
+        labels: &[String],
+        mut cursor: usize,
+        mut checked: Option<Vec<bool>>,
+        check: F,
+    ) -> Result<Vec<usize>>
+    where
+        F: Fn(&[usize]) -> ::std::result::Result<(), String>,
+    {
+        let style = self.style();
+        let theme = self.current_theme();
+        self.print_message(&style)?;
+        let mut raw = RawMode::enable(&mut self.state.console)?;
+        let mut error = None;
+        let mut drawn = 0;
+        loop {
+            let lines = menu_lines(&theme, labels, cursor, checked.as_deref(), error.as_ref());
+            clear(&mut *raw, drawn)?;
+            for line in &lines {
+                raw.write_line(line, &style)?;
+            }
+            drawn = lines.len();
+            match raw.read_key()? {
+                Key::Up | Key::Char('k') => cursor = (cursor + labels.len() - 1) % labels.len(),
+                Key::Down | Key::Char('j') => cursor = (cursor + 1) % labels.len(),
+                Key::Home => cursor = 0,
+                Key::End => cursor = labels.len() - 1,
+                Key::Char(' ') => {
+                    if let Some(ref mut checked) = checked {
+                        checked[cursor] = !checked[cursor];
+                    }
+                }
+                Key::Enter => {
+                    let selection: Vec<usize> = match checked {
+                        Some(ref checked) => (0..labels.len()).filter(|&i| checked[i]).collect(),
+                        None => vec![cursor],
+                    };
+                    match check(&selection) {
+                        Ok(()) => {
+                            clear(&mut *raw, drawn)?;
+                            let chosen: Vec<&str> =
+                                selection.iter().map(|&i| labels[i].as_str()).collect();
+                            raw.write_line(&chosen.join(", "), &theme.success)?;
+                            return Ok(selection);
+                        }
+                        Err(reason) => error = Some(reason),
+                    }
+                }
+                Key::Ctrl('c') | Key::Escape => {
+                    clear(&mut *raw, drawn)?;
+                    let cancelled = Error::Interrupted.to_string();
+                    raw.write_line(&cancelled, &theme.failure)?;
+                    return Err(Error::Interrupted);
+                }
+                _ => (),
+            }
+        }
+    }
+
+    fn print_options(&mut self, labels: &[String]) -> Result<()> {
+        let style = self.style();
+        let width = labels.len().to_string().len();
+        for (i, label) in labels.iter().enumerate() {
+            self.print(&style, format!("  {:>2$}) {}", i + 1, label, width))?;
+        }
+        Ok(())
+    }
+
+    fn print_checklist(&mut self, labels: &[String], checked: &[usize]) -> Result<()> {
+        let style = self.style();
+        let theme = self.current_theme();
+        let width = labels.len().to_string().len();
+        for (i, label) in labels.iter().enumerate() {
+            let mark = match checked.contains(&i) {
+                true => &theme.checked,
+                false => &theme.unchecked,
+            };
+            self.print(
+                &style,
+                format!("  {} {:>3$}) {}", mark, i + 1, label, width),
+            )?;
+        }
+        Ok(())
+    }
+
+    /// Asks for one of `options`, by number or label, or with the arrow keys on
+    /// a terminal. The validator is given the chosen option's label. Confirmation,
+    /// transformers and the attempt limit only apply when answers are typed.
+    pub fn ask_select<'o, V: Display>(&mut self, options: &'o [V]) -> Result<(usize, &'o V)> {
+        if options.is_empty() {
+            return Err(Error::NoOptions);
+        }
+        let labels = labels(options);
+        self.default_option(&labels);
+        let (validator, message) = (self.state.validate, self.state.validation_message);
+        if self.state.console.is_tty() && !self.is_unattended() {
+            let cursor = self.state.default.and_then(|d| parse_option(&labels, d));
+            let selection = self.navigate(&labels, cursor.unwrap_or(0), None, |selection| {
+                check_label(validator, message, &labels[selection[0]])
+            })?;
+            return Ok((selection[0], &options[selection[0]]));
+        }
+        // Only optional selects can be left unanswered.
+        let index = self.type_option(&labels, false)?.ok_or(Error::Eof)?;
+        Ok((index, &options[index]))
+    }
+
+    /// Like `ask_select`, but an empty answer with no default gives `None`. The
+    /// options are always listed to be typed, as the arrow keys have no way to skip.
+    pub(crate) fn ask_optional_select<'o, V: Display>(
+        &mut self,
+        options: &'o [V],
+    ) -> Result<Option<(usize, &'o V)>> {
+        if options.is_empty() {
+            return Err(Error::NoOptions);
+        }
+        let labels = labels(options);
+        self.default_option(&labels);
+        let index = self.type_option(&labels, true)?;
+        Ok(index.map(|index| (index, &options[index])))
+    }
+
+    /// Shows a default that is one of `labels` as the hint, and ignores any other.
+    fn default_option(&mut self, labels: &[String]) {
+        match self.state.default.map(|d| parse_option(labels, d)) {
+            Some(Some(default)) => self.state.hint = Some(labels[default].clone()),
+            Some(None) => self.state.default = None,
+            None => (),
+        }
+    }
+
+    /// Lists `labels` and asks for one of them by number or label.
+    fn type_option(&mut self, labels: &[String], optional: bool) -> Result<Option<usize>> {
+        let (validator, message) = (self.state.validate, self.state.validation_message);
+        let reason = match message {
+            Some(msg) => msg.to_string(),
+            None => format!("Please choose an option from 1 to {}.", labels.len()),
+        };
+        if !self.is_unattended() {
+            self.print_options(labels)?;
+        }
+        // The validator sees the chosen option's label rather than what was typed.
+        self.state.validate = None;
+        let index = self.answer(|answer| {
+            if optional && answer.trim().is_empty() {
+                return Ok(None);
+            }
+            let index = parse_option(labels, &answer).ok_or_else(|| reason.clone())?;
+            check_label(validator, message, &labels[index]).map(|_| Some(index))
+        });
+        self.state.validate = validator;
+        index
+    }
+
+    /// Asks for any number of `options`, as a typed list or by toggling them
+    /// with space on a terminal. Validators, confirmation, transformers and the
+    /// attempt limit only apply when answers are typed.
+    pub fn ask_multi_select<'o, V: Display>(
+        &mut self,
+        options: &'o [V],
+    ) -> Result<Vec<(usize, &'o V)>> {
+        if options.is_empty() {
+            return Err(Error::NoOptions);
+        }
+        let labels = labels(options);
+        let mut preselected: Vec<usize> = self
+            .state
+            .preselect
+            .unwrap_or(&[])
+            .iter()
+            .cloned()
+            .filter(|&i| i < labels.len())
+            .collect();
+        preselected.sort();
+        preselected.dedup();
+        if !preselected.is_empty() {
+            let numbers: Vec<String> = preselected.iter().map(|i| (i + 1).to_string()).collect();
+            self.state.hint = Some(numbers.join(","));
+        }
+        let (min, max) = (self.state.min_selections, self.state.max_selections);
+        if self.state.console.is_tty() && !self.is_unattended() {
+            let checked = (0..labels.len())
+                .map(|i| preselected.contains(&i))
+                .collect();
+            let selection = self.navigate(&labels, 0, Some(checked), |selection| {
+                check_count(selection.len(), min, max)
+            })?;
+            return Ok(selection.into_iter().map(|i| (i, &options[i])).collect());
+        }
+        if !self.is_unattended() {
+            self.print_checklist(&labels, &preselected)?;
+        }
+        let message = self.state.validation_message;
+        let selection = self
+            .answer(|answer| check_selection(&labels, &answer, &preselected, min, max, message))?;
+        Ok(selection.into_iter().map(|i| (i, &options[i])).collect())
+    }
+
+    pub fn preselect(&mut self, indices: &'ask [usize]) -> &mut StateBuilder<'ask, T> {
+        self.state.preselect = Some(indices);
+        self
+    }
+
+    pub fn min_selections(&mut self, min: usize) -> &mut StateBuilder<'ask, T> {
+        self.state.min_selections = Some(min);
+        self
+    }
+
+    pub fn max_selections(&mut self, max: usize) -> &mut StateBuilder<'ask, T> {
+        self.state.max_selections = Some(max);
+        self
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{parse_option, parse_selection};
+    use input;
+    use testing::{strip_ansi, Script};
+    use {Error, MemoryTerminal, Theme};
+
+    const REGIONS: &[&str] = &["eu-west-1", "us-east-1", "ap-south-1"];
+
+    #[test]
+    fn parses_numbers_and_labels() {
+        let labels: Vec<String> = REGIONS.iter().map(|r| r.to_string()).collect();
+        assert_eq!(parse_option(&labels, "2\n"), Some(1));
+        assert_eq!(parse_option(&labels, "AP-SOUTH-1"), Some(2));
+        assert_eq!(parse_option(&labels, "4"), None);
+        assert_eq!(parse_option(&labels, "0"), None);
+    }
+
+    #[test]
+    fn can_select_an_option() {
+        let mut script = Script::new();
+        script
+            .expect("  1) eu-west-1\n  2) us-east-1\n  3) ap-south-1\nRegion:")
+            .send("7")
+            .expect("Please choose an option from 1 to 3.\nRegion:")
+            .send("us-east-1");
+        let selected = script.run(|term| {
+            input("Region")
+                .prompt(&':')
+                .terminal(term)
+                .ask_select(REGIONS)
+                .unwrap()
+        });
+        assert_eq!(selected, (1, &"us-east-1"));
+    }
+
+    #[test]
+    fn can_select_the_default() {
+        let mut script = Script::new();
+        script
+            .expect("  1) 10\n  2) 20\n  3) 30\nReplicas [30]:")
+            .send("");
+        let selected = script.run(|term| {
+            input("Replicas")
+                .prompt(&':')
+                .default("3")
+                .terminal(term)
+                .ask_select(&[10, 20, 30])
+                .unwrap()
+        });
+        assert_eq!(selected, (2, &30));
+    }
+
+    #[test]
+    fn validates_the_chosen_label() {
+        let mut script = Script::new();
+        script
+            .send("1")
+            .expect("Pick a US region.\nRegion")
+            .send("2");
+        let us = |label: &str| match label.starts_with("us-") {
+            true => Ok(()),
+            false => Err("Pick a US region.".to_string()),
+        };
+        let selected = script.run(|term| {
+            input("Region")
+                .validate(&us)
+                .terminal(term)
+                .ask_select(REGIONS)
+                .unwrap()
+        });
+        assert_eq!(selected, (1, &"us-east-1"));
+    }
+
+    #[test]
+    fn validates_the_label_chosen_with_arrow_keys() {
+        let mut term = MemoryTerminal::new("\rj\r");
+        term.tty(true);
+        let us = |label: &str| match label.starts_with("us-") {
+            true => Ok(()),
+            false => Err("Pick a US region.".to_string()),
+        };
+        let selected = input("Region")
+            .validate(&us)
+            .terminal(&mut term)
+            .ask_select(REGIONS)
+            .unwrap();
+        assert_eq!(selected, (1, &"us-east-1"));
+        assert!(term.output().contains("Pick a US region."));
+    }
+
+    #[test]
+    fn ignores_a_default_that_is_not_an_option() {
+        let mut script = Script::new();
+        script
+            .expect("  1) eu-west-1\n  2) us-east-1\n  3) ap-south-1\nRegion")
+            .send("")
+            .expect("Please choose an option from 1 to 3.\nRegion")
+            .send("3");
+        let selected = script.run(|term| {
+            input("Region")
+                .default("nope")
+                .terminal(term)
+                .ask_select(REGIONS)
+                .unwrap()
+        });
+        assert_eq!(selected, (2, &"ap-south-1"));
+    }
+
+    #[test]
+    fn cannot_select_from_nothing() {
+        let options: &[&str] = &[];
+        assert!(input("Region").ask_select(options).is_err());
+    }
+
+    #[test]
+    fn parses_lists_and_ranges() {
+        let labels: Vec<String> = (1..8).map(|n| format!("host{}", n)).collect();
+        assert_eq!(
+            parse_selection(&labels, "1,3 5-7\n"),
+            Ok(vec![0, 2, 4, 5, 6])
+        );
+        assert_eq!(parse_selection(&labels, "host2, 2 ,1-2"), Ok(vec![0, 1]));
+        assert!(parse_selection(&labels, "6-9").is_err());
+        assert!(parse_selection(&labels, "3-1").is_err());
+        assert_eq!(parse_selection(&labels, ""), Ok(vec![]));
+    }
+
+    #[test]
+    fn can_select_several_options() {
+        let mut script = Script::new();
+        script
+            .expect("  [ ] 1) eu-west-1\n  [x] 2) us-east-1\n  [ ] 3) ap-south-1\nRegions [2]:")
+            .send("1 4")
+            .expect("4 is not one of the options.\nRegions [2]:")
+            .send("1,3");
+        let selected = script.run(|term| {
+            input("Regions")
+                .prompt(&':')
+                .preselect(&[1])
+                .terminal(term)
+                .ask_multi_select(REGIONS)
+                .unwrap()
+        });
+        assert_eq!(selected, vec![(0, &"eu-west-1"), (2, &"ap-south-1")]);
+    }
+
+    #[test]
+    fn can_accept_the_preselected_options() {
+        let mut script = Script::new();
+        script.send("");
+        let selected = input("Regions")
+            .preselect(&[2, 0])
+            .terminal(&mut script)
+            .ask_multi_select(REGIONS)
+            .unwrap();
+        assert_eq!(selected, vec![(0, &"eu-west-1"), (2, &"ap-south-1")]);
+    }
+
+    #[test]
+    fn can_limit_the_number_of_options() {
+        let mut script = Script::new();
+        script
+            .send("")
+            .expect("Please choose at least 1 option(s).\nRegions")
+            .send("1-3")
+            .expect("Please choose at most 2 option(s).\nRegions")
+            .send("2");
+        let selected = script.run(|term| {
+            input("Regions")
+                .min_selections(1)
+                .max_selections(2)
+                .terminal(term)
+                .ask_multi_select(REGIONS)
+                .unwrap()
+        });
+        assert_eq!(selected, vec![(1, &"us-east-1")]);
+    }
+
+    #[test]
+    fn can_select_with_arrow_keys() {
+        let mut term = MemoryTerminal::new("\x1b[Bkj\r");
+        term.tty(true);
+        let selected = input("Region")
+            .default("2")
+            .terminal(&mut term)
+            .ask_select(REGIONS)
+            .unwrap();
+        assert_eq!(selected, (2, &"ap-south-1"));
+        assert!(!term.is_raw());
+        assert!(
+            strip_ansi(term.output()).starts_with("Region [us-east-1]\n  eu-west-1\n> us-east-1\n")
+        );
+        assert!(term.output().ends_with("\x1b[3A\r\x1b[Jap-south-1\n"));
+    }
+
+    #[test]
+    fn can_toggle_options_with_space() {
+        let mut term = MemoryTerminal::new(" \r\x1b[B\x1b[B \r");
+        term.tty(true);
+        let selected = input("Regions")
+            .min_selections(1)
+            .preselect(&[0])
+            .terminal(&mut term)
+            .ask_multi_select(REGIONS)
+            .unwrap();
+        assert_eq!(selected, vec![(2, &"ap-south-1")]);
+        assert!(term
+            .output()
+            .contains("Please choose at least 1 option(s)."));
+    }
+
+    #[test]
+    fn restores_the_terminal_when_interrupted() {
+        let mut term = MemoryTerminal::new("j\x03");
+        term.tty(true);
+        let err = input("Region")
+            .terminal(&mut term)
+            .ask_select(REGIONS)
+            .unwrap_err();
+        match err {
+            Error::Interrupted => assert!(!term.is_raw()),
+            _ => panic!("unexpected error: {}", err),
+        }
+        assert!(term.output().ends_with("Interrupted by the user\n"));
+    }
+
+    #[test]
+    fn draws_menus_with_the_theme() {
+        let mut script = Script::new();
+        script
+            .expect("  * 1) eu-west-1\n  - 2) us-east-1\n  - 3) ap-south-1\nRegions [1]?")
+            .send("");
+        let theme = Theme {
+            checked: "*".to_string(),
+            unchecked: "-".to_string(),
+            prompt_symbol: Some('?'),
+            ..Theme::plain()
+        };
+        script.run(|term| {
+            input("Regions")
+                .preselect(&[0])
+                .theme(&theme)
+                .terminal(term)
+                .ask_multi_select(REGIONS)
+                .unwrap()
+        });
+
+        let mut term = MemoryTerminal::new("j\r");
+        term.tty(true);
+        let theme = Theme::colourful();
+        input("Region")
+            .theme(&theme)
+            .terminal(&mut term)
+            .ask_select(REGIONS)
+            .unwrap();
+        assert!(strip_ansi(term.output()).contains("\n❯ us-east-1\n"));
+    }
+}
@@ -0,0 +1,467 @@
+use serde::de::{self, Deserialize, Deserializer, Visitor};
+use std::env;
+use std::fmt;
+use std::str::FromStr;
+
+/// The colours of the first sixteen palette entries, as xterm draws them.
+const PALETTE: [(u8, u8, u8); 16] = [
+    (0, 0, 0),
+    (205, 0, 0),
+    (0, 205, 0),
+    (205, 205, 0),
+    (0, 0, 238),
+    (205, 0, 205),
+    (0, 205, 205),
+    (229, 229, 229),
+    (127, 127, 127),
+    (255, 0, 0),
+    (0, 255, 0),
+    (255, 255, 0),
+    (92, 92, 255),
+    (255, 0, 255),
+    (0, 255, 255),
+    (255, 255, 255),
+];
+
+const BASIC: [Colour; 16] = [
+    Colour::Black,
+    Colour::Red,
+    Colour::Green,
+    Colour::Yellow,
+    Colour::Blue,
+    Colour::Magenta,
+    Colour::Cyan,
+    Colour::White,
+    Colour::BrightBlack,
+    Colour::BrightRed,
+    Colour::BrightGreen,
+    Colour::BrightYellow,
+    Colour::BrightBlue,
+    Colour::BrightMagenta,
+    Colour::BrightCyan,
+    Colour::BrightWhite,
+];
+
+/// The levels of each channel in the 6x6x6 colour cube of the 256-colour palette.
+const CUBE: [u8; 6] = [0, 95, 135, 175, 215, 255];
+
+/// How many colours a terminal can show.
+#[derive(Clone, Copy, Debug, PartialEq, PartialOrd)]
+pub enum ColourSupport {
+    /// No escape codes at all, not even for bold or underline.
+    Plain,
+    /// The sixteen ANSI colours.
+    Basic,
+    /// The 256-colour palette.
+    Ansi256,
+    /// Any RGB colour.
+    TrueColour,
+}
+
+impl ColourSupport {
+    /// Works out what output going to a terminal, or not when `tty` is false, can
+    /// show from `NO_COLOR`, `CLICOLOR_FORCE`, `TERM` and `COLORTERM`.
+    pub fn detect(tty: bool) -> ColourSupport {
+        detect_with(tty, |name| env::var(name).ok())
+    }
+
+    /// The most `TERM` and `COLORTERM` say the terminal can show, and at least
+    /// the basic colours, for when colour has been asked for regardless.
+    pub fn depth() -> ColourSupport {
+        depth_with(|name| env::var(name).ok())
+    }
+}
+
+fn detect_with<F: Fn(&str) -> Option<String>>(tty: bool, var: F) -> ColourSupport {
+    let set = |name| var(name).is_some_and(|value| !value.is_empty());
+    let term = var("TERM").unwrap_or_default();
+    let forced = set("CLICOLOR_FORCE") && var("CLICOLOR_FORCE").as_deref() != Some("0");
+    match set("NO_COLOR") || (!forced && (!tty || term == "dumb")) {
+        true => ColourSupport::Plain,
+        false => depth_with(var),
+    }
+}
+
+fn depth_with<F: Fn(&str) -> Option<String>>(var: F) -> ColourSupport {
+    match var("COLORTERM").as_deref() {
+        Some("truecolor") | Some("24bit") => ColourSupport::TrueColour,
+        _ if var("TERM").is_some_and(|term| term.contains("256color")) => ColourSupport::Ansi256,
+        _ => ColourSupport::Basic,
+    }
+}
+
+/// Whether prompts draw their styles.
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub enum ColourChoice {
+    /// Whatever the destination, using as many colours as the environment says
+    /// the terminal has.
+    Always,
+    Never,
+    /// Only on stdout when it is a terminal that can show them, or through a
+    /// custom `Terminal`, which decides for itself.
+    #[default]
+    Auto,
+}
+
+fn distance(a: (u8, u8, u8), b: (u8, u8, u8)) -> u32 {
+    let channel = |x: u8, y: u8| (i32::from(x) - i32::from(y)).pow(2) as u32;
+    channel(a.0, b.0) + channel(a.1, b.1) + channel(a.2, b.2)
+}
+
+/// The RGB value of a 256-colour palette entry.
+fn palette_rgb(index: u8) -> (u8, u8, u8) {
+    match index {
+        0..=15 => PALETTE[index as usize],
+        16..=231 => {
+            let i = index as usize - 16;
+            (CUBE[i / 36], CUBE[i / 6 % 6], CUBE[i % 6])
+        }
+        _ => {
+            let grey = 8 + (index - 232) * 10;
+            (grey, grey, grey)
+        }
+    }
+}
+
+/// The nearest entry to `rgb` among the palette entries in `candidates`.
+fn nearest<I: Iterator<Item = u8>>(rgb: (u8, u8, u8), candidates: I) -> u8 {
+    candidates
+        .min_by_key(|&index| distance(rgb, palette_rgb(index)))
+        .unwrap_or(0)
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Colour {
+    /// Whatever colour the terminal uses when none is set.
+    Default,
+    Black,
+    Red,
+    Green,
+    Yellow,
+    Blue,
+    Magenta,
+    Cyan,
+    White,
+    BrightBlack,
+    BrightRed,
+    BrightGreen,
+    BrightYellow,
+    BrightBlue,
+    BrightMagenta,
+    BrightCyan,
+    BrightWhite,
+    /// An index into the 256-colour palette.
+    Fixed(u8),
+    Rgb(u8, u8, u8),
+}
+
+impl Colour {
+    /// The nearest colour a terminal with `support` can show.
+    pub fn downgrade(self, support: ColourSupport) -> Colour {
+        match (self, support) {
+            (Colour::Rgb(r, g, b), ColourSupport::Ansi256) => {
+                Colour::Fixed(nearest((r, g, b), 16..=255))
+            }
+            (Colour::Rgb(r, g, b), ColourSupport::Basic) => {
+                BASIC[nearest((r, g, b), 0..16) as usize]
+            }
+            (Colour::Fixed(index), ColourSupport::Basic) => {
+                BASIC[nearest(palette_rgb(index), 0..16) as usize]
+            }
+            (colour, _) => colour,
+        }
+    }
+
+    /// The SGR parameters selecting this colour, for the foreground when `base` is
+    /// 30 and the background when it is 40.
+    fn code(self, base: u8) -> String {
+        let basic = |offset: u8| (base + offset).to_string();
+        let bright = |offset: u8| (base + 60 + offset).to_string();
+        match self {
+            Colour::Default => basic(9),
+            Colour::Black => basic(0),
+            Colour::Red => basic(1),
+            Colour::Green => basic(2),
+            Colour::Yellow => basic(3),
+            Colour::Blue => basic(4),
+            Colour::Magenta => basic(5),
+            Colour::Cyan => basic(6),
+            Colour::White => basic(7),
+            Colour::BrightBlack => bright(0),
+            Colour::BrightRed => bright(1),
+            Colour::BrightGreen => bright(2),
+            Colour::BrightYellow => bright(3),
+            Colour::BrightBlue => bright(4),
+            Colour::BrightMagenta => bright(5),
+            Colour::BrightCyan => bright(6),
+            Colour::BrightWhite => bright(7),
+            Colour::Fixed(index) => format!("{};5;{}", base + 8, index),
+            Colour::Rgb(r, g, b) => format!("{};2;{};{};{}", base + 8, r, g, b),
+        }
+    }
+}
+
+/// Parses colour names such as `red`, `bright-blue` and `default`, palette indexes
+/// such as `208`, and hex codes such as `#ff8700`.
+impl FromStr for Colour {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Colour, String> {
+        let name: String = s
+            .trim()
+            .chars()
+            .filter(|c| !"-_ ".contains(*c))
+            .collect::<String>()
+            .to_lowercase();
+        let colour = match name.as_str() {
+            "default" => Colour::Default,
+            "black" => Colour::Black,
+            "red" => Colour::Red,
+            "green" => Colour::Green,
+            "yellow" => Colour::Yellow,
+            "blue" => Colour::Blue,
+            "magenta" => Colour::Magenta,
+            "cyan" => Colour::Cyan,
+            "white" => Colour::White,
+            "brightblack" | "grey" | "gray" => Colour::BrightBlack,
+            "brightred" => Colour::BrightRed,
+            "brightgreen" => Colour::BrightGreen,
+            "brightyellow" => Colour::BrightYellow,
+            "brightblue" => Colour::BrightBlue,
+            "brightmagenta" => Colour::BrightMagenta,
+            "brightcyan" => Colour::BrightCyan,
+            "brightwhite" => Colour::BrightWhite,
+            hex if hex.starts_with('#') && hex.len() == 7 && hex.is_ascii() => {
+                let channel = |i| u8::from_str_radix(&hex[i..i + 2], 16);
+                match (channel(1), channel(3), channel(5)) {
+                    (Ok(r), Ok(g), Ok(b)) => Colour::Rgb(r, g, b),
+                    _ => return Err(format!("{} is not a colour.", s)),
+                }
+            }
+            index => match index.parse() {
+                Ok(index) => Colour::Fixed(index),
+                Err(_) => return Err(format!("{} is not a colour.", s)),
+            },
+        };
+        Ok(colour)
+    }
+}
+
+impl<'de> Deserialize<'de> for Colour {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Colour, D::Error> {
+        struct ColourVisitor;
+
+        impl<'de> Visitor<'de> for ColourVisitor {
+            type Value = Colour;
+
+            fn expecting(&self, f: &mut fmt::Formatter) -> fmt::Result {
+                write!(f, "a colour name, hex code or palette index")
+            }
+
+            fn visit_str<E: de::Error>(self, s: &str) -> Result<Colour, E> {
+                s.parse().map_err(E::custom)
+            }
+
+            fn visit_u64<E: de::Error>(self, index: u64) -> Result<Colour, E> {
+                match index {
+                    0..=255 => Ok(Colour::Fixed(index as u8)),
+                    _ => Err(E::custom(format!("{} is not a palette index.", index))),
+                }
+            }
+
+            fn visit_i64<E: de::Error>(self, index: i64) -> Result<Colour, E> {
+                match index {
+                    0..=255 => Ok(Colour::Fixed(index as u8)),
+                    _ => Err(E::custom(format!("{} is not a palette index.", index))),
+                }
+            }
+        }
+
+        deserializer.deserialize_any(ColourVisitor)
+    }
+}
+
+/// How a piece of text is drawn. Unset colours and attributes are left as the
+/// terminal has them.
+#[derive(Clone, Copy, Debug, Default, Deserialize, PartialEq)]
+#[serde(default, deny_unknown_fields)]
+pub struct Style {
+    pub fg: Option<Colour>,
+    pub bg: Option<Colour>,
+    pub bold: bool,
+    pub dim: bool,
+    pub italic: bool,
+    pub underline: bool,
+    pub reverse: bool,
+}
+
+impl Style {
+    pub fn new() -> Style {
+        Default::default()
+    }
+
+    pub fn fg(mut self, colour: Colour) -> Style {
+        self.fg = Some(colour);
+        self
+    }
+
+    pub fn bg(mut self, colour: Colour) -> Style {
+        self.bg = Some(colour);
+        self
+    }
+
+    pub fn bold(mut self) -> Style {
+        self.bold = true;
+        self
+    }
+
+    pub fn dim(mut self) -> Style {
+        self.dim = true;
+        self
+    }
+
+    pub fn italic(mut self) -> Style {
+        self.italic = true;
+        self
+    }
+
+    pub fn underline(mut self) -> Style {
+        self.underline = true;
+        self
+    }
+
+    pub fn reverse(mut self) -> Style {
+        self.reverse = true;
+        self
+    }
+
+    /// The nearest style a terminal with `support` can show, which for
+    /// `ColourSupport::Plain` is no style at all.
+    pub fn downgrade(&self, support: ColourSupport) -> Style {
+        match support {
+            ColourSupport::Plain => Style::default(),
+            _ => Style {
+                fg: self.fg.map(|colour| colour.downgrade(support)),
+                bg: self.bg.map(|colour| colour.downgrade(support)),
+                ..*self
+            },
+        }
+    }
+
+    pub fn is_plain(&self) -> bool {
+        *self == Style::default()
+    }
+
+    /// The escape sequence switching to this style, empty for a plain style.
+    pub fn prefix(&self) -> String {
+        let attributes = [
+            (self.bold, "1"),
+            (self.dim, "2"),
+            (self.italic, "3"),
+            (self.underline, "4"),
+            (self.reverse, "7"),
+        ];
+        let mut codes: Vec<String> = attributes
+            .iter()
+            .filter(|&&(on, _)| on)
+            .map(|&(_, code)| code.to_string())
+            .collect();
+        codes.extend(self.fg.map(|colour| colour.code(30)));
+        codes.extend(self.bg.map(|colour| colour.code(40)));
+        match codes.is_empty() {
+            true => String::new(),
+            false => format!("\x1b[{}m", codes.join(";")),
+        }
+    }
+
+    /// Wraps `text` in the ANSI escape codes for this style.
+    pub fn paint(&self, text: &str) -> String {
+        match self.is_plain() {
+            true => text.to_string(),
+            false => format!("{}{}\x1b[0m", self.prefix(), text),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{detect_with, Colour, ColourSupport, Style};
+
+    #[test]
+    fn paints_attributes_and_colours() {
+        let hint = Style::new().fg(Colour::Blue).dim();
+        assert_eq!(hint.paint("aging"), "\x1b[2;34maging\x1b[0m");
+        let banner = Style::new().bold().underline().bg(Colour::BrightYellow);
+        assert_eq!(banner.paint("!"), "\x1b[1;4;103m!\x1b[0m");
+        assert_eq!(Style::default().paint("aging"), "aging");
+    }
+
+    #[test]
+    fn parses_colours() {
+        assert_eq!("Bright-Red".parse(), Ok(Colour::BrightRed));
+        assert_eq!("208".parse(), Ok(Colour::Fixed(208)));
+        assert_eq!("#ff8700".parse(), Ok(Colour::Rgb(255, 135, 0)));
+        assert!("mauve".parse::<Colour>().is_err());
+        assert!("#1é234".parse::<Colour>().is_err());
+    }
+
+    #[test]
+    fn paints_extended_colours() {
+        assert_eq!(
+            Style::new().fg(Colour::Fixed(208)).prefix(),
+            "\x1b[38;5;208m"
+        );
+        assert_eq!(
+            Style::new()
+                .fg(Colour::Default)
+                .bg(Colour::Rgb(1, 2, 3))
+                .prefix(),
+            "\x1b[39;48;2;1;2;3m"
+        );
+    }
+
+    #[test]
+    fn detects_colour_support() {
+        let env = |vars: &'static [(&'static str, &'static str)]| {
+            move |name: &str| {
+                vars.iter()
+                    .find(|&&(key, _)| key == name)
+                    .map(|&(_, value)| value.to_string())
+            }
+        };
+        let xterm = &[("TERM", "xterm-256color")];
+        assert_eq!(detect_with(true, env(xterm)), ColourSupport::Ansi256);
+        assert_eq!(detect_with(false, env(xterm)), ColourSupport::Plain);
+        let truecolour = &[("TERM", "xterm"), ("COLORTERM", "truecolor")];
+        assert_eq!(
+            detect_with(true, env(truecolour)),
+            ColourSupport::TrueColour
+        );
+        let no_colour = &[("TERM", "xterm"), ("NO_COLOR", "1")];
+        assert_eq!(detect_with(true, env(no_colour)), ColourSupport::Plain);
+        assert_eq!(
+            detect_with(true, env(&[("TERM", "dumb")])),
+            ColourSupport::Plain
+        );
+        let forced = &[("TERM", "dumb"), ("CLICOLOR_FORCE", "1")];
+        assert_eq!(detect_with(false, env(forced)), ColourSupport::Basic);
+    }
+
+    #[test]
+    fn downgrades_colours() {
+        let orange = Colour::Rgb(255, 135, 0);
+        assert_eq!(orange.downgrade(ColourSupport::TrueColour), orange);
+        assert_eq!(orange.downgrade(ColourSupport::Ansi256), Colour::Fixed(208));
+        assert_eq!(orange.downgrade(ColourSupport::Basic), Colour::Yellow);
+        assert_eq!(
+            Colour::Fixed(244).downgrade(ColourSupport::Basic),
+            Colour::BrightBlack
+        );
+        assert_eq!(
+            Colour::Fixed(3).downgrade(ColourSupport::Basic),
+            Colour::Yellow
+        );
+        let style = Style::new().bold().fg(orange);
+        assert_eq!(style.downgrade(ColourSupport::Plain), Style::default());
+    }
+}
@@ -0,0 +1,534 @@
+use keys::{parse_key, Key};
+use rpassword::read_password;
+use std::io::{self, stdin, stdout, BufRead, Cursor, Write};
+use std::ops::{Deref, DerefMut};
+use style::{ColourChoice, ColourSupport, Style};
+
+/// Everything a prompt needs from the terminal it is talking to.
+pub trait Terminal {
+    /// Reads a line including its line ending, or an empty string at end of input.
+    fn read_line(&mut self) -> io::Result<String>;
+
+    /// Reads a line without echoing it, with the line ending removed. Fails with
+    /// `UnexpectedEof` at end of input.
+    fn read_secret(&mut self) -> io::Result<String>;
+
+    fn write(&mut self, text: &str) -> io::Result<()>;
+
+    fn write_styled(&mut self, text: &str, _style: &Style) -> io::Result<()> {
+        self.write(text)
+    }
+
+    fn write_line(&mut self, text: &str, style: &Style) -> io::Result<()> {
+        self.write_styled(text, style)?;
+        self.write("\n")
+    }
+
+    /// Reads a single key press. Only called while in raw mode.
+    fn read_key(&mut self) -> io::Result<Key> {
+        Err(io::Error::other("Terminal does not support reading keys."))
+    }
+
+    fn set_raw_mode(&mut self, _raw: bool) -> io::Result<()> {
+        Ok(())
+    }
+
+    fn is_tty(&self) -> bool {
+        false
+    }
+
+    /// The terminal's size as `(columns, rows)`, if known.
+    fn size(&self) -> Option<(u16, u16)> {
+        None
+    }
+}
+
+/// Keeps a terminal in raw mode until dropped, so it is restored however the
+/// prompt ends, including by panicking.
+pub(crate) struct RawMode<'t> {
+    terminal: &'t mut dyn Terminal,
+}
+
+impl<'t> RawMode<'t> {
+    pub fn enable(terminal: &'t mut dyn Terminal) -> io::Result<RawMode<'t>> {
+        terminal.set_raw_mode(true)?;
+        Ok(RawMode { terminal })
+    }
+}
+
+impl<'t> Deref for RawMode<'t> {
+    type Target = dyn Terminal + 't;
+
+    fn deref(&self) -> &Self::Target {
+        self.terminal
+    }
+}
+
+impl<'t> DerefMut for RawMode<'t> {
+    fn deref_mut(&mut self) -> &mut Self::Target {
+        self.terminal
+    }
+}
+
+impl<'t> Drop for RawMode<'t> {
+    fn drop(&mut self) {
+        let _ = self.terminal.set_raw_mode(false);
+    }
+}
+
+/// The process's own stdin and stdout.
+pub struct StdTerminal {
+    cooked: Option<sys::Mode>,
+    pending: Vec<u8>,
+    colours: ColourSupport,
+}
+
+impl Default for StdTerminal {
+    fn default() -> StdTerminal {
+        StdTerminal {
+            cooked: None,
+            pending: Vec::new(),
+            colours: ColourSupport::detect(sys::is_output_tty()),
+        }
+    }
+}
+
+impl StdTerminal {
+    /// Overrides the colour support detected from stdout and the environment.
+    pub fn colours(&mut self, colours: ColourSupport) -> &mut StdTerminal {
+        self.colours = colours;
+        self
+    }
+}
+
+impl Terminal for StdTerminal {
+    fn read_line(&mut self) -> io::Result<String> {
+        let mut buf = String::new();
+        stdin().read_line(&mut buf)?;
+        Ok(buf)
+    }
+
+    fn read_secret(&mut self) -> io::Result<String> {
+        read_password()
+    }
+
+    fn write(&mut self, text: &str) -> io::Result<()> {
+        let mut out = stdout();
+        out.write_all(text.as_bytes())?;
+        out.flush()
+    }
+
+    fn write_styled(&mut self, text: &str, style: &Style) -> io::Result<()> {
+        self.write(&style.downgrade(self.colours).paint(text))
+    }
+
+    fn read_key(&mut self) -> io::Result<Key> {
+        loop {
+            if let Some((key, len)) = parse_key(&self.pending) {
+                self.pending.drain(..len);
+                return Ok(key);
+            }
+            let mut buf = [0; 32];
+            match sys::read(&mut buf)? {
+                0 => return Err(io::ErrorKind::UnexpectedEof.into()),
+                n => self.pending.extend_from_slice(&buf[..n]),
+            }
+        }
+    }
+
+    fn set_raw_mode(&mut self, raw: bool) -> io::Result<()> {
+        match (raw, self.cooked.take()) {
+            (true, None) => self.cooked = Some(sys::enable_raw_mode()?),
+            (false, Some(cooked)) => sys::restore(&cooked)?,
+            (_, cooked) => self.cooked = cooked,
+        }
+        Ok(())
+    }
+
+    fn is_tty(&self) -> bool {
+        sys::is_tty()
+    }
+
+    fn size(&self) -> Option<(u16, u16)> {
+        sys::size()
+    }
+}
+
+/// A terminal that answers from a fixed input and records what was written to it.
+#[derive(Default)]
+pub struct MemoryTerminal {
+    input: Cursor<Vec<u8>>,
+    output: String,
+    tty: bool,
+    raw: bool,
+    size: Option<(u16, u16)>,
+}
+
+impl MemoryTerminal {
+    pub fn new<S: Into<String>>(input: S) -> MemoryTerminal {
+        MemoryTerminal {
+            input: Cursor::new(input.into().into_bytes()),
+            ..Default::default()
+        }
+    }
+
+    pub fn output(&self) -> &str {
+        &self.output
+    }
+
+    pub fn tty(&mut self, tty: bool) -> &mut MemoryTerminal {
+        self.tty = tty;
+        self
+    }
+
+    pub fn resize(&mut self, columns: u16, rows: u16) -> &mut MemoryTerminal {
+        self.size = Some((columns, rows));
+        self
+    }
+
+    pub fn is_raw(&self) -> bool {
+        self.raw
+    }
+}
+
+impl Terminal for MemoryTerminal {
+    fn read_line(&mut self) -> io::Result<String> {
+        let mut buf = String::new();
+        self.input.read_line(&mut buf)?;
+        Ok(buf)
+    }
+
+    fn read_secret(&mut self) -> io::Result<String> {
+        self.read_line().and_then(secret)
+    }
+
+    fn write(&mut self, text: &str) -> io::Result<()> {
+        self.output.push_str(text);
+        Ok(())
+    }
+
+    fn read_key(&mut self) -> io::Result<Key> {
+        let position = self.input.position() as usize;
+        match parse_key(&self.input.get_ref()[position..]) {
+            Some((key, len)) => {
+                self.input.set_position((position + len) as u64);
+                Ok(key)
+            }
+            None => Err(io::ErrorKind::UnexpectedEof.into()),
+        }
+    }
+
+    fn set_raw_mode(&mut self, raw: bool) -> io::Result<()> {
+        self.raw = raw;
+        Ok(())
+    }
+
+    fn is_tty(&self) -> bool {
+        self.tty
+    }
+
+    fn size(&self) -> Option<(u16, u16)> {
+        self.size
+    }
+}
+
+fn secret(line: String) -> io::Result<String> {
+    match line.is_empty() {
+        true => Err(io::Error::new(
+            io::ErrorKind::UnexpectedEof,
+            "unexpected end of file",
+        )),
+        false => Ok(::strip_line_ending(line)),
+    }
+}
+
+/// Routes a prompt's I/O to `redirect_in`/`redirect_out` when set, and to its
+/// terminal otherwise.
+#[derive(Default)]
+pub(crate) struct Console<'ask> {
+    pub input: Option<Box<dyn BufRead + 'ask>>,
+    pub output: Option<&'ask mut dyn Write>,
+    pub terminal: Option<&'ask mut dyn Terminal>,
+    colours: Option<ColourSupport>,
+    choice: ColourChoice,
+    std: StdTerminal,
+}
+
+impl<'ask> Console<'ask> {
+    pub fn colours(&mut self, colours: ColourSupport) {
+        self.colours = Some(colours);
+        self.update_std();
+    }
+
+    pub fn choose_colour(&mut self, choice: ColourChoice) {
+        self.choice = choice;
+        self.update_std();
+    }
+
+    /// Never colouring wins over any support that was set, which wins over
+    /// what the policy would detect.
+    fn update_std(&mut self) {
+        let colours = match (self.choice, self.colours) {
+            (ColourChoice::Never, _) => ColourSupport::Plain,
+            (_, Some(colours)) => colours,
+            (ColourChoice::Always, None) => ColourSupport::depth(),
+            (ColourChoice::Auto, None) => ColourSupport::detect(sys::is_output_tty()),
+        };
+        self.std.colours(colours);
+    }
+
+    /// What styles are downgraded to before being written, or `None` when a
+    /// custom terminal is left to decide.
+    fn support(&self) -> Option<ColourSupport> {
+        let chosen = match self.choice {
+            ColourChoice::Always => Some(ColourSupport::depth()),
+            ColourChoice::Never => return Some(ColourSupport::Plain),
+            ColourChoice::Auto => None,
+        };
+        match (&self.output, &self.terminal) {
+            (&Some(_), _) => self.colours.or(chosen).or(Some(ColourSupport::Plain)),
+            (_, &Some(_)) => self.colours.or(chosen),
+            _ => Some(self.std.colours),
+        }
+    }
+
+    /// The style as it will reach the screen.
+    fn effective_style(&self, style: &Style) -> Style {
+        self.support()
+            .map_or(*style, |colours| style.downgrade(colours))
+    }
+
+    /// The style to write escape codes for by hand, or `None` when there is
+    /// nothing to draw or a custom terminal is left to decide.
+    pub fn raw_style(&self, style: &Style) -> Option<Style> {
+        self.support()
+            .map(|colours| style.downgrade(colours))
+            .filter(|style| !style.is_plain())
+    }
+
+    fn terminal(&mut self) -> &mut dyn Terminal {
+        match self.terminal {
+            Some(ref mut t) => &mut **t,
+            None => &mut self.std,
+        }
+    }
+}
+
+impl<'ask> Terminal for Console<'ask> {
+    fn read_line(&mut self) -> io::Result<String> {
+        match self.input {
+            Some(ref mut r) => {
+                let mut buf = String::new();
+                r.read_line(&mut buf)?;
+                Ok(buf)
+            }
+            None => self.terminal().read_line(),
+        }
+    }
+
+    fn read_secret(&mut self) -> io::Result<String> {
+        match self.input {
+            Some(_) => self.read_line().and_then(secret),
+            None => self.terminal().read_secret(),
+        }
+    }
+
+    fn write(&mut self, text: &str) -> io::Result<()> {
+        match self.output {
+            Some(ref mut w) => w.write_all(text.as_bytes()),
+            None => self.terminal().write(text),
+        }
+    }
+
+    fn write_styled(&mut self, text: &str, style: &Style) -> io::Result<()> {
+        let style = self.effective_style(style);
+        match self.output {
+            Some(ref mut w) => w.write_all(style.paint(text).as_bytes()),
+            None => self.terminal().write_styled(text, &style),
+        }
+    }
+
+    fn read_key(&mut self) -> io::Result<Key> {
+        self.terminal().read_key()
+    }
+
+    fn set_raw_mode(&mut self, raw: bool) -> io::Result<()> {
+        self.terminal().set_raw_mode(raw)
+    }
+
+    fn is_tty(&self) -> bool {
+        match (&self.input, &self.output, &self.terminal) {
+            (Some(_), _, _) | (_, Some(_), _) => false,
+            (None, None, Some(t)) => t.is_tty(),
+            (None, None, None) => self.std.is_tty(),
+        }
+    }
+
+    fn size(&self) -> Option<(u16, u16)> {
+        match self.terminal {
+            Some(ref t) => t.size(),
+            None => self.std.size(),
+        }
+    }
+}
+
+#[cfg(unix)]
+mod sys {
+    use libc;
+    use std::io;
+
+    pub type Mode = libc::termios;
+
+    pub fn is_tty() -> bool {
+        unsafe { libc::isatty(libc::STDIN_FILENO) == 1 && is_output_tty() }
+    }
+
+    pub fn is_output_tty() -> bool {
+        unsafe { libc::isatty(libc::STDOUT_FILENO) == 1 }
+    }
+
+    fn check(ret: libc::c_int) -> io::Result<()> {
+        match ret {
+            0 => Ok(()),
+            _ => Err(io::Error::last_os_error()),
+        }
+    }
+
+    /// Switches stdin to raw mode, returning the mode to restore afterwards.
+    pub fn enable_raw_mode() -> io::Result<Mode> {
+        let mut cooked: Mode = unsafe { ::std::mem::zeroed() };
+        check(unsafe { libc::tcgetattr(libc::STDIN_FILENO, &mut cooked) })?;
+        let mut raw = cooked;
+        raw.c_iflag &= !(libc::BRKINT | libc::ICRNL | libc::INPCK | libc::ISTRIP | libc::IXON);
+        raw.c_lflag &= !(libc::ECHO | libc::ICANON | libc::IEXTEN | libc::ISIG);
+        raw.c_cc[libc::VMIN] = 1;
+        raw.c_cc[libc::VTIME] = 0;
+        check(unsafe { libc::tcsetattr(libc::STDIN_FILENO, libc::TCSAFLUSH, &raw) })?;
+        Ok(cooked)
+    }
+
+    pub fn restore(mode: &Mode) -> io::Result<()> {
+        check(unsafe { libc::tcsetattr(libc::STDIN_FILENO, libc::TCSAFLUSH, mode) })
+    }
+
+    pub fn read(buf: &mut [u8]) -> io::Result<usize> {
+        let n = unsafe { libc::read(libc::STDIN_FILENO, buf.as_mut_ptr() as *mut _, buf.len()) };
+        match n {
+            -1 => Err(io::Error::last_os_error()),
+            n => Ok(n as usize),
+        }
+    }
+
+    pub fn size() -> Option<(u16, u16)> {
+        let mut size: libc::winsize = unsafe { ::std::mem::zeroed() };
+        match unsafe { libc::ioctl(libc::STDOUT_FILENO, libc::TIOCGWINSZ, &mut size) } {
+            0 if size.ws_col > 0 => Some((size.ws_col, size.ws_row)),
+            _ => None,
+        }
+    }
+}
+
+#[cfg(not(unix))]
+mod sys {
+    use std::io;
+
+    pub type Mode = ();
+
+    pub fn is_tty() -> bool {
+        false
+    }
+
+    pub fn is_output_tty() -> bool {
+        false
+    }
+
+    pub fn enable_raw_mode() -> io::Result<Mode> {
+        Err(io::Error::other(
+            "Raw mode is not supported on this platform.",
+        ))
+    }
+
+    pub fn restore(_mode: &Mode) -> io::Result<()> {
+        Ok(())
+    }
+
+    pub fn read(_buf: &mut [u8]) -> io::Result<usize> {
+        Err(io::Error::other(
+            "Reading keys is not supported on this platform.",
+        ))
+    }
+
+    pub fn size() -> Option<(u16, u16)> {
+        None
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{Console, MemoryTerminal, RawMode, Style, Terminal};
+    use keys::Key;
+    use std::io::Cursor;
+    use std::panic::{self, AssertUnwindSafe};
+
+    #[test]
+    fn memory_terminal_records_output() {
+        let mut term = MemoryTerminal::new("");
+        term.write_line("Hello", &Style::default()).unwrap();
+        assert_eq!(term.output(), "Hello\n");
+    }
+
+    #[test]
+    fn memory_terminal_strips_secret_line_endings() {
+        let mut term = MemoryTerminal::new("secret\r\nplain\n");
+        assert_eq!(term.read_secret().unwrap(), "secret");
+        assert_eq!(term.read_line().unwrap(), "plain\n");
+        assert_eq!(term.read_line().unwrap(), "");
+        assert!(term.read_secret().is_err());
+    }
+
+    #[test]
+    fn console_prefers_redirected_input() {
+        let mut term = MemoryTerminal::new("terminal\n");
+        term.tty(true);
+        let mut console = Console {
+            input: Some(Box::new(Cursor::new(&b"redirected\n"[..]))),
+            terminal: Some(&mut term),
+            ..Default::default()
+        };
+        assert!(!console.is_tty());
+        assert_eq!(console.read_line().unwrap(), "redirected\n");
+    }
+
+    #[test]
+    fn memory_terminal_reads_keys() {
+        let mut term = MemoryTerminal::new("\x1b[Bj\r");
+        assert_eq!(term.read_key().unwrap(), Key::Down);
+        assert_eq!(term.read_key().unwrap(), Key::Char('j'));
+        assert_eq!(term.read_key().unwrap(), Key::Enter);
+        assert!(term.read_key().is_err());
+    }
+
+    #[test]
+    fn raw_mode_is_restored_after_a_panic() {
+        let mut term = MemoryTerminal::new("");
+        let result = panic::catch_unwind(AssertUnwindSafe(|| {
+            let _raw = RawMode::enable(&mut term).unwrap();
+            panic!("Prompt failed.");
+        }));
+        assert!(result.is_err());
+        assert!(!term.is_raw());
+    }
+
+    #[test]
+    fn console_is_not_a_tty_when_output_is_redirected() {
+        let mut term = MemoryTerminal::new("");
+        term.tty(true);
+        let mut sink = ::std::io::sink();
+        let console = Console {
+            output: Some(&mut sink),
+            terminal: Some(&mut term),
+            ..Default::default()
+        };
+        assert!(!console.is_tty());
+    }
+}
@@ -0,0 +1,280 @@
+//! Scripted conversations for testing code that asks questions.
+//!
+//! A `Script` is a `Terminal` that checks each prompt against what the script
+//! expects before handing back a canned reply, and panics with a diff when the
+//! conversation goes somewhere else.
+
+use std::collections::VecDeque;
+use std::io;
+use {Style, Terminal};
+
+enum Step {
+    Expect(String),
+    Send(String),
+    Eof,
+}
+
+#[derive(Default)]
+pub struct Script {
+    steps: VecDeque<Step>,
+    pending: String,
+    transcript: String,
+    exchanges: usize,
+    keep_ansi: bool,
+}
+
+impl Script {
+    pub fn new() -> Script {
+        Default::default()
+    }
+
+    /// Expects everything written since the previous reply to equal `prompt`,
+    /// ignoring trailing newlines.
+    pub fn expect<S: Into<String>>(&mut self, prompt: S) -> &mut Script {
+        self.steps.push_back(Step::Expect(prompt.into()));
+        self
+    }
+
+    pub fn send<S: Into<String>>(&mut self, reply: S) -> &mut Script {
+        self.steps.push_back(Step::Send(reply.into()));
+        self
+    }
+
+    pub fn send_eof(&mut self) -> &mut Script {
+        self.steps.push_back(Step::Eof);
+        self
+    }
+
+    /// Keeps the escape codes of styled output instead of stripping them.
+    pub fn keep_ansi(&mut self) -> &mut Script {
+        self.keep_ansi = true;
+        self
+    }
+
+    /// Everything written and sent so far.
+    pub fn transcript(&self) -> &str {
+        &self.transcript
+    }
+
+    /// Runs `f` against the script, then checks the whole script was used.
+    pub fn run<F, R>(&mut self, f: F) -> R
+    where
+        F: FnOnce(&mut Script) -> R,
+    {
+        let result = f(self);
+        self.finish();
+        result
+    }
+
+    /// Panics unless every step of the script was played.
+    pub fn finish(&mut self) {
+        if let Some(Step::Expect(_)) = self.steps.front() {
+            self.check_prompt();
+        }
+        if !self.steps.is_empty() {
+            panic!(
+                "Conversation ended after {} exchange(s) with {} step(s) of the script unused.\n{}",
+                self.exchanges,
+                self.steps.len(),
+                self.transcript
+            );
+        }
+    }
+
+    fn check_prompt(&mut self) {
+        let pending = ::std::mem::take(&mut self.pending);
+        if let Some(Step::Expect(expected)) = self.steps.front() {
+            let actual = strip_ansi_unless(&pending, self.keep_ansi);
+            if expected.trim_end_matches('\n') != actual.trim_end_matches('\n') {
+                panic!(
+                    "Prompt {} did not match the script.\n{}",
+                    self.exchanges + 1,
+                    diff(expected, &actual)
+                );
+            }
+            self.steps.pop_front();
+        }
+    }
+
+    fn reply(&mut self) -> Option<String> {
+        self.check_prompt();
+        let reply = match self.steps.pop_front() {
+            Some(Step::Send(reply)) => Some(reply),
+            Some(Step::Eof) => None,
+            Some(Step::Expect(prompt)) => panic!(
+                "Expected prompt {:?} before the reply to prompt {}.",
+                prompt,
+                self.exchanges + 1
+            ),
+            None => panic!(
+                "Prompt {} asked for an answer the script does not have.\n{}",
+                self.exchanges + 1,
+                self.transcript
+            ),
+        };
+        self.exchanges += 1;
+        if let Some(ref reply) = reply {
+            self.transcript.push_str(reply);
+            self.transcript.push('\n');
+        }
+        reply
+    }
+}
+
+impl Terminal for Script {
+    fn read_line(&mut self) -> io::Result<String> {
+        Ok(self.reply().map(|r| r + "\n").unwrap_or_default())
+    }
+
+    fn read_secret(&mut self) -> io::Result<String> {
+        self.reply()
+            .ok_or_else(|| io::Error::new(io::ErrorKind::UnexpectedEof, "unexpected end of file"))
+    }
+
+    fn write(&mut self, text: &str) -> io::Result<()> {
+        self.pending.push_str(text);
+        self.transcript
+            .push_str(&strip_ansi_unless(text, self.keep_ansi));
+        Ok(())
+    }
+
+    fn write_styled(&mut self, text: &str, style: &Style) -> io::Result<()> {
+        match self.keep_ansi {
+            true => self.write(&style.paint(text)),
+            false => self.write(text),
+        }
+    }
+}
+
+/// Removes ANSI escape sequences from `text`.
+pub fn strip_ansi(text: &str) -> String {
+    let mut out = String::with_capacity(text.len());
+    let mut chars = text.chars().peekable();
+    while let Some(c) = chars.next() {
+        if c != '\x1b' {
+            out.push(c);
+            continue;
+        }
+        if chars.peek() == Some(&'[') {
+            chars.next();
+            for c in chars.by_ref() {
+                if ('@'..='~').contains(&c) {
+                    break;
+                }
+            }
+        } else {
+            chars.next();
+        }
+    }
+    out
+}
+
+fn strip_ansi_unless(text: &str, keep_ansi: bool) -> String {
+    match keep_ansi {
+        true => text.to_string(),
+        false => strip_ansi(text),
+    }
+}
+
+/// A line diff of `expected` against `actual`, marking removed lines with `-`
+/// and added lines with `+`.
+fn diff(expected: &str, actual: &str) -> String {
+    let old: Vec<&str> = expected.trim_end_matches('\n').lines().collect();
+    let new: Vec<&str> = actual.trim_end_matches('\n').lines().collect();
+    let mut common = vec![vec![0; new.len() + 1]; old.len() + 1];
+    for i in (0..old.len()).rev() {
+        for j in (0..new.len()).rev() {
+            common[i][j] = match old[i] == new[j] {
+                true => common[i + 1][j + 1] + 1,
+                false => common[i + 1][j].max(common[i][j + 1]),
+            };
+        }
+    }
+    let mut out = String::from("--- expected\n+++ actual\n");
+    let (mut i, mut j) = (0, 0);
+    while i < old.len() || j < new.len() {
+        if i < old.len() && j < new.len() && old[i] == new[j] {
+            out.push_str(&format!("  {:?}\n", old[i]));
+            i += 1;
+            j += 1;
+        } else if j == new.len() || (i < old.len() && common[i + 1][j] >= common[i][j + 1]) {
+            out.push_str(&format!("- {:?}\n", old[i]));
+            i += 1;
+        } else {
+            out.push_str(&format!("+ {:?}\n", new[j]));
+            j += 1;
+        }
+    }
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{diff, strip_ansi, Script};
+    use {input, Colour};
+
+    #[test]
+    fn can_play_a_conversation() {
+        let mut script = Script::new();
+        script
+            .expect("Port [8080]:")
+            .send("eighty")
+            .expect("invalid digit found in string\nPort [8080]:")
+            .send("80");
+        let port = script.run(|term| {
+            input("Port")
+                .prompt(&':')
+                .default("8080")
+                .terminal(term)
+                .ask_as::<u16>()
+                .unwrap()
+        });
+        assert_eq!(port, 80);
+    }
+
+    #[test]
+    #[should_panic(expected = "Prompt 1 did not match the script.")]
+    fn panics_when_the_prompt_differs() {
+        let mut script = Script::new();
+        script.expect("Port:").send("80");
+        input("Host")
+            .prompt(&':')
+            .terminal(&mut script)
+            .ask()
+            .unwrap();
+    }
+
+    #[test]
+    #[should_panic(expected = "1 step(s) of the script unused")]
+    fn panics_when_replies_are_left_over() {
+        let mut script = Script::new();
+        script.send("80").send("443");
+        script.run(|term| input("Port").terminal(term).ask().unwrap());
+    }
+
+    #[test]
+    fn can_keep_ansi_codes() {
+        let mut script = Script::new();
+        script.keep_ansi().expect("\x1b[31mPort\x1b[0m").send("80");
+        script.run(|term| {
+            input("Port")
+                .fg_colour(Colour::Red)
+                .terminal(term)
+                .ask()
+                .unwrap()
+        });
+    }
+
+    #[test]
+    fn strips_ansi_codes() {
+        assert_eq!(strip_ansi("\x1b[1;31mPort\x1b[0m:"), "Port:");
+    }
+
+    #[test]
+    fn diffs_by_line() {
+        assert_eq!(
+            diff("Host\nPort:", "Host\nPort [80]:"),
+            "--- expected\n+++ actual\n  \"Host\"\n- \"Port:\"\n+ \"Port [80]:\"\n"
+        );
+    }
+}
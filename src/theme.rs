@@ -0,0 +1,214 @@
+use std::fs;
+use std::path::Path;
+use std::sync::Mutex;
+use toml;
+use {Colour, Error, Result, Style};
+
+/// The theme used by prompts that are not given one.
+static THEME: Mutex<Option<Theme>> = Mutex::new(None);
+
+/// How every part of a prompt is drawn. Parts left as `None` take the message's
+/// style, and anything set on the prompt itself overrides its theme.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Theme {
+    pub message: Style,
+    pub prompt: Option<Style>,
+    /// Ends messages of prompts that do not set a prompt character.
+    pub prompt_symbol: Option<char>,
+    pub default: Option<Style>,
+    pub input: Style,
+    pub error: Option<Style>,
+    pub hint: Style,
+    /// Marks the option under the cursor in a menu, and `unselected` the rest.
+    pub selected: String,
+    pub unselected: String,
+    /// Mark options that are and are not chosen in a multi-select.
+    pub checked: String,
+    pub unchecked: String,
+    /// Styles the summary left behind when a menu is answered.
+    pub success: Style,
+    /// Styles the summary left behind when a menu is cancelled.
+    pub failure: Style,
+}
+
+impl Default for Theme {
+    fn default() -> Theme {
+        Theme::plain()
+    }
+}
+
+/// A theme as written in a file, where every field is optional.
+#[derive(Default, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+struct ThemeFile {
+    base: Option<String>,
+    message: Option<Style>,
+    prompt: Option<Style>,
+    prompt_symbol: Option<char>,
+    default: Option<Style>,
+    input: Option<Style>,
+    error: Option<Style>,
+    hint: Option<Style>,
+    selected: Option<String>,
+    unselected: Option<String>,
+    checked: Option<String>,
+    unchecked: Option<String>,
+    success: Option<Style>,
+    failure: Option<Style>,
+}
+
+impl Theme {
+    /// No colours, as prompts have always looked.
+    pub fn plain() -> Theme {
+        Theme {
+            message: Style::default(),
+            prompt: None,
+            prompt_symbol: None,
+            default: None,
+            input: Style::default(),
+            error: None,
+            hint: Style::new().dim(),
+            selected: ">".to_string(),
+            unselected: " ".to_string(),
+            checked: "[x]".to_string(),
+            unchecked: "[ ]".to_string(),
+            success: Style::default(),
+            failure: Style::default(),
+        }
+    }
+
+    pub fn colourful() -> Theme {
+        Theme {
+            message: Style::new().bold(),
+            prompt: Some(Style::new().fg(Colour::Cyan)),
+            prompt_symbol: Some('›'),
+            default: Some(Style::new().fg(Colour::BrightBlack)),
+            input: Style::new().fg(Colour::Cyan),
+            error: Some(Style::new().fg(Colour::Red)),
+            hint: Style::new().fg(Colour::BrightBlack),
+            selected: "❯".to_string(),
+            unselected: " ".to_string(),
+            checked: "◉".to_string(),
+            unchecked: "◯".to_string(),
+            success: Style::new().fg(Colour::Green),
+            failure: Style::new().fg(Colour::Red),
+        }
+    }
+
+    /// Attributes only, for terminals whose colours are unknown or unwanted.
+    pub fn monochrome() -> Theme {
+        Theme {
+            message: Style::new().bold(),
+            default: Some(Style::new().dim()),
+            error: Some(Style::new().bold().underline()),
+            success: Style::new().bold(),
+            failure: Style::new().dim(),
+            ..Theme::plain()
+        }
+    }
+
+    /// One of the built-in themes by name.
+    pub fn named(name: &str) -> Option<Theme> {
+        match name {
+            "plain" => Some(Theme::plain()),
+            "colourful" | "colorful" => Some(Theme::colourful()),
+            "monochrome" => Some(Theme::monochrome()),
+            _ => None,
+        }
+    }
+
+    /// Reads a theme from TOML. Anything left out is taken from the built-in theme
+    /// named by `base`, or the plain theme.
+    pub fn from_toml(text: &str) -> Result<Theme> {
+        let file: ThemeFile = toml::from_str(text).map_err(|err| Error::Parse(Box::new(err)))?;
+        let base = file.base.as_ref().map_or("plain", String::as_str);
+        let theme = match Theme::named(base) {
+            Some(theme) => theme,
+            None => {
+                return Err(Error::Parse(
+                    format!("There is no built-in theme called {}.", base).into(),
+                ))
+            }
+        };
+        Ok(Theme {
+            message: file.message.unwrap_or(theme.message),
+            prompt: file.prompt.or(theme.prompt),
+            prompt_symbol: file.prompt_symbol.or(theme.prompt_symbol),
+            default: file.default.or(theme.default),
+            input: file.input.unwrap_or(theme.input),
+            error: file.error.or(theme.error),
+            hint: file.hint.unwrap_or(theme.hint),
+            selected: file.selected.unwrap_or(theme.selected),
+            unselected: file.unselected.unwrap_or(theme.unselected),
+            checked: file.checked.unwrap_or(theme.checked),
+            unchecked: file.unchecked.unwrap_or(theme.unchecked),
+            success: file.success.unwrap_or(theme.success),
+            failure: file.failure.unwrap_or(theme.failure),
+        })
+    }
+
+    pub fn load<P: AsRef<Path>>(path: P) -> Result<Theme> {
+        let text = fs::read_to_string(path)?;
+        Theme::from_toml(&text)
+    }
+}
+
+/// Sets the theme for every prompt not given its own.
+pub fn set_theme(theme: Theme) {
+    if let Ok(mut current) = THEME.lock() {
+        *current = Some(theme);
+    }
+}
+
+pub(crate) fn current() -> Theme {
+    THEME
+        .lock()
+        .ok()
+        .and_then(|theme| theme.clone())
+        .unwrap_or_default()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::Theme;
+    use {Colour, Style};
+
+    #[test]
+    fn reads_overrides_from_toml() {
+        let theme = Theme::from_toml(
+            r##"
+            base = "monochrome"
+            prompt_symbol = "?"
+            checked = "[*]"
+
+            [message]
+            fg = "bright-yellow"
+            italic = true
+
+            [default]
+            fg = 244
+
+            [error]
+            fg = "#ff0000"
+            "##,
+        )
+        .unwrap();
+        assert_eq!(
+            theme.message,
+            Style::new().fg(Colour::BrightYellow).italic()
+        );
+        assert_eq!(theme.default, Some(Style::new().fg(Colour::Fixed(244))));
+        assert_eq!(theme.error, Some(Style::new().fg(Colour::Rgb(255, 0, 0))));
+        assert_eq!(theme.prompt_symbol, Some('?'));
+        assert_eq!(theme.checked, "[*]");
+        assert_eq!(theme.success, Theme::monochrome().success);
+    }
+
+    #[test]
+    fn rejects_unknown_themes_and_fields() {
+        assert!(Theme::from_toml("base = \"neon\"").is_err());
+        assert!(Theme::from_toml("[message]\nblink = true").is_err());
+        assert!(Theme::from_toml("[message]\nfg = \"mauve\"").is_err());
+        assert!(Theme::from_toml("[message]\nfg = \"#1é234\"").is_err());
+    }
+}
@@ -0,0 +1,52 @@
+/// Rewrites an answer before it is validated, such as by trimming it.
+pub trait Transformer {
+    fn transform(&self, answer: &str) -> String;
+}
+
+impl<F> Transformer for F
+where
+    F: Fn(&str) -> String,
+{
+    fn transform(&self, answer: &str) -> String {
+        self(answer)
+    }
+}
+
+pub(crate) struct Trim;
+
+impl Transformer for Trim {
+    fn transform(&self, answer: &str) -> String {
+        answer.trim().to_string()
+    }
+}
+
+pub(crate) struct Lowercase;
+
+impl Transformer for Lowercase {
+    fn transform(&self, answer: &str) -> String {
+        answer.to_lowercase()
+    }
+}
+
+/// Replaces each run of whitespace with one space, dropping it from the ends.
+pub(crate) struct CollapseWhitespace;
+
+impl Transformer for CollapseWhitespace {
+    fn transform(&self, answer: &str) -> String {
+        answer.split_whitespace().collect::<Vec<_>>().join(" ")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{CollapseWhitespace, Lowercase, Transformer, Trim};
+
+    #[test]
+    fn transforms_answers() {
+        assert_eq!(Trim.transform(" web 1\t"), "web 1");
+        assert_eq!(Lowercase.transform("Web1"), "web1");
+        assert_eq!(CollapseWhitespace.transform(" New \t York "), "New York");
+        let slug = |answer: &str| answer.replace(' ', "-");
+        assert_eq!(slug.transform("new york"), "new-york");
+    }
+}
@@ -0,0 +1,101 @@
+/// Decides whether an answer is acceptable, explaining why not when it isn't.
+pub trait Validator {
+    /// Checks `answer`, without its line ending. The message of an `Err` is shown
+    /// to the user before they are asked again.
+    fn validate(&self, answer: &str) -> Result<(), String>;
+
+    /// Accepts answers that both validators accept, explaining the first rejection.
+    fn and<V: Validator>(self, other: V) -> And<Self, V>
+    where
+        Self: Sized,
+    {
+        And(self, other)
+    }
+
+    /// Accepts answers that either validator accepts, explaining the second
+    /// rejection when neither does.
+    fn or<V: Validator>(self, other: V) -> Or<Self, V>
+    where
+        Self: Sized,
+    {
+        Or(self, other)
+    }
+
+    /// Accepts the answers this validator rejects, explaining rejections with
+    /// `message`.
+    fn not(self, message: &str) -> Not<Self>
+    where
+        Self: Sized,
+    {
+        Not(self, message.to_string())
+    }
+}
+
+impl<F> Validator for F
+where
+    F: Fn(&str) -> Result<(), String>,
+{
+    fn validate(&self, answer: &str) -> Result<(), String> {
+        self(answer)
+    }
+}
+
+pub struct And<A, B>(A, B);
+
+impl<A: Validator, B: Validator> Validator for And<A, B> {
+    fn validate(&self, answer: &str) -> Result<(), String> {
+        self.0
+            .validate(answer)
+            .and_then(|()| self.1.validate(answer))
+    }
+}
+
+pub struct Or<A, B>(A, B);
+
+impl<A: Validator, B: Validator> Validator for Or<A, B> {
+    fn validate(&self, answer: &str) -> Result<(), String> {
+        self.0.validate(answer).or_else(|_| self.1.validate(answer))
+    }
+}
+
+pub struct Not<V>(V, String);
+
+impl<V: Validator> Validator for Not<V> {
+    fn validate(&self, answer: &str) -> Result<(), String> {
+        match self.0.validate(answer) {
+            Ok(()) => Err(self.1.clone()),
+            Err(_) => Ok(()),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::Validator;
+
+    struct MaxLength(usize);
+
+    impl Validator for MaxLength {
+        fn validate(&self, answer: &str) -> Result<(), String> {
+            if answer.chars().count() <= self.0 {
+                Ok(())
+            } else {
+                Err(format!("Use at most {} characters.", self.0))
+            }
+        }
+    }
+
+    #[test]
+    fn validates_with_closures_and_structs() {
+        let not_empty = |answer: &str| match answer {
+            "" => Err("Enter something.".to_string()),
+            _ => Ok(()),
+        };
+        assert_eq!(not_empty.validate(""), Err("Enter something.".to_string()));
+        assert_eq!(not_empty.validate("web1"), Ok(()));
+        assert_eq!(
+            MaxLength(3).validate("web1"),
+            Err("Use at most 3 characters.".to_string())
+        );
+    }
+}
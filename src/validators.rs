@@ -0,0 +1,349 @@
+//! Ready-made validators for common checks, to pass to `validate` or combine
+//! with `and`, `or` and `not`.
+
+use regex::Regex;
+use std::fmt::Display;
+use std::net::IpAddr;
+use std::path::Path;
+use std::str::FromStr;
+pub use validate::{And, Not, Or};
+use {Error, Result, Validator};
+
+type Outcome = ::std::result::Result<(), String>;
+
+fn check(ok: bool, message: &str) -> Outcome {
+    match ok {
+        true => Ok(()),
+        false => Err(message.to_string()),
+    }
+}
+
+/// Rejects answers that are empty or only whitespace.
+pub struct NonEmpty;
+
+impl Validator for NonEmpty {
+    fn validate(&self, answer: &str) -> Outcome {
+        check(!answer.trim().is_empty(), "An answer is required.")
+    }
+}
+
+/// Limits how many characters an answer may have.
+pub struct Length {
+    min: usize,
+    max: Option<usize>,
+}
+
+impl Length {
+    pub fn at_least(min: usize) -> Length {
+        Length { min, max: None }
+    }
+
+    pub fn at_most(max: usize) -> Length {
+        Length {
+            min: 0,
+            max: Some(max),
+        }
+    }
+
+    pub fn between(min: usize, max: usize) -> Length {
+        Length {
+            min,
+            max: Some(max),
+        }
+    }
+}
+
+impl Validator for Length {
+    fn validate(&self, answer: &str) -> Outcome {
+        let len = answer.chars().count();
+        match self.max {
+            Some(max) if len > max => Err(format!("Use at most {} characters.", max)),
+            _ if len < self.min => Err(format!("Use at least {} characters.", self.min)),
+            _ => Ok(()),
+        }
+    }
+}
+
+/// Accepts answers matching a regular expression.
+pub struct Matches {
+    regex: Regex,
+    message: String,
+}
+
+impl Matches {
+    pub fn new(pattern: &str) -> Result<Matches> {
+        let regex = Regex::new(pattern).map_err(|err| Error::Parse(Box::new(err)))?;
+        let message = format!("The answer must match {}.", pattern);
+        Ok(Matches { regex, message })
+    }
+
+    /// Explains rejections with `message` rather than by showing the pattern.
+    pub fn message(mut self, message: &str) -> Matches {
+        self.message = message.to_string();
+        self
+    }
+}
+
+impl Validator for Matches {
+    fn validate(&self, answer: &str) -> Outcome {
+        check(self.regex.is_match(answer), &self.message)
+    }
+}
+
+/// Accepts numbers from `min` to `max` inclusive.
+pub struct InRange<N> {
+    min: N,
+    max: N,
+}
+
+impl<N: FromStr + PartialOrd + Display> InRange<N> {
+    pub fn new(min: N, max: N) -> InRange<N> {
+        InRange { min, max }
+    }
+}
+
+impl<N: FromStr + PartialOrd + Display> Validator for InRange<N> {
+    fn validate(&self, answer: &str) -> Outcome {
+        match answer.trim().parse::<N>() {
+            Ok(ref n) if *n >= self.min && *n <= self.max => Ok(()),
+            _ => Err(format!("Enter a number from {} to {}.", self.min, self.max)),
+        }
+    }
+}
+
+/// Accepts only the given answers.
+pub struct OneOf<'o> {
+    options: &'o [&'o str],
+}
+
+impl<'o> OneOf<'o> {
+    pub fn new(options: &'o [&'o str]) -> OneOf<'o> {
+        OneOf { options }
+    }
+}
+
+impl<'o> Validator for OneOf<'o> {
+    fn validate(&self, answer: &str) -> Outcome {
+        match self.options.contains(&answer) {
+            true => Ok(()),
+            false => Err(format!("Choose one of {}.", self.options.join(", "))),
+        }
+    }
+}
+
+fn is_hostname(answer: &str) -> bool {
+    let is_label = |label: &str| {
+        !label.is_empty()
+            && label.len() <= 63
+            && !label.starts_with('-')
+            && !label.ends_with('-')
+            && label.chars().all(|c| c.is_ascii_alphanumeric() || c == '-')
+    };
+    !answer.is_empty() && answer.len() <= 253 && answer.split('.').all(is_label)
+}
+
+/// Accepts host names such as `db-1.example.com`.
+pub struct Hostname;
+
+impl Validator for Hostname {
+    fn validate(&self, answer: &str) -> Outcome {
+        check(is_hostname(answer), "Enter a host name, like example.com.")
+    }
+}
+
+/// Accepts IPv4 and IPv6 addresses.
+pub struct IpAddress;
+
+impl Validator for IpAddress {
+    fn validate(&self, answer: &str) -> Outcome {
+        check(
+            answer.parse::<IpAddr>().is_ok(),
+            "Enter an IP address, like 192.168.0.1.",
+        )
+    }
+}
+
+/// Accepts addresses of the form `name@example.com`.
+pub struct Email;
+
+impl Validator for Email {
+    fn validate(&self, answer: &str) -> Outcome {
+        let valid = match answer.rfind('@') {
+            Some(at) => {
+                let (name, domain) = (&answer[..at], &answer[at + 1..]);
+                !name.is_empty()
+                    && !name.contains(|c: char| c.is_whitespace() || c == '@')
+                    && domain.contains('.')
+                    && is_hostname(domain)
+            }
+            None => false,
+        };
+        check(valid, "Enter an email address, like name@example.com.")
+    }
+}
+
+/// Accepts absolute URLs such as `https://example.com/path`.
+pub struct Url;
+
+impl Validator for Url {
+    fn validate(&self, answer: &str) -> Outcome {
+        let valid = match answer.find("://") {
+            Some(end) => {
+                let scheme = &answer[..end];
+                let host = answer[end + 3..].split(['/', '?', '#']).next();
+                scheme.starts_with(|c: char| c.is_ascii_alphabetic())
+                    && scheme
+                        .chars()
+                        .all(|c| c.is_ascii_alphanumeric() || "+.-".contains(c))
+                    && host.is_some_and(|host| !host.is_empty())
+                    && !answer.contains(char::is_whitespace)
+            }
+            None => false,
+        };
+        check(valid, "Enter a URL, like https://example.com.")
+    }
+}
+
+/// Accepts paths to anything that exists.
+pub struct PathExists;
+
+impl Validator for PathExists {
+    fn validate(&self, answer: &str) -> Outcome {
+        check(Path::new(answer).exists(), "There is nothing at that path.")
+    }
+}
+
+/// Accepts paths to directories.
+pub struct IsDir;
+
+impl Validator for IsDir {
+    fn validate(&self, answer: &str) -> Outcome {
+        check(Path::new(answer).is_dir(), "That is not a directory.")
+    }
+}
+
+/// Accepts paths to files or directories that can be written to.
+pub struct IsWritable;
+
+impl Validator for IsWritable {
+    fn validate(&self, answer: &str) -> Outcome {
+        check(is_writable(Path::new(answer)), "That path is not writable.")
+    }
+}
+
+#[cfg(unix)]
+fn is_writable(path: &Path) -> bool {
+    use libc;
+    use std::ffi::CString;
+    use std::os::unix::ffi::OsStrExt;
+
+    match CString::new(path.as_os_str().as_bytes()) {
+        Ok(path) => unsafe { libc::access(path.as_ptr(), libc::W_OK) == 0 },
+        Err(_) => false,
+    }
+}
+
+#[cfg(not(unix))]
+fn is_writable(path: &Path) -> bool {
+    use std::fs;
+
+    fs::metadata(path)
+        .map(|metadata| !metadata.permissions().readonly())
+        .unwrap_or(false)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::{env, fs, process};
+
+    #[test]
+    fn checks_text() {
+        assert!(NonEmpty.validate("  ").is_err());
+        assert!(NonEmpty.validate("web1").is_ok());
+        assert_eq!(
+            Length::between(2, 3).validate("webby"),
+            Err("Use at most 3 characters.".to_string())
+        );
+        assert!(Length::at_least(2).validate("é").is_err());
+        assert!(Length::at_most(4).validate("web1").is_ok());
+        assert!(OneOf::new(&["dev", "prod"]).validate("prod").is_ok());
+        assert_eq!(
+            OneOf::new(&["dev", "prod"]).validate("qa"),
+            Err("Choose one of dev, prod.".to_string())
+        );
+    }
+
+    #[test]
+    fn checks_patterns() {
+        let branch = Matches::new("^[a-z][a-z0-9-]*$").unwrap();
+        assert!(branch.validate("fix-42").is_ok());
+        assert_eq!(
+            branch.validate("Fix"),
+            Err("The answer must match ^[a-z][a-z0-9-]*$.".to_string())
+        );
+        let branch = branch.message("Use lower case.");
+        assert_eq!(branch.validate("Fix"), Err("Use lower case.".to_string()));
+        assert!(Matches::new("(").is_err());
+    }
+
+    #[test]
+    fn checks_numbers() {
+        let port = InRange::new(1u16, 1024);
+        assert!(port.validate("80").is_ok());
+        assert!(port.validate("8080").is_err());
+        assert_eq!(
+            port.validate("http"),
+            Err("Enter a number from 1 to 1024.".to_string())
+        );
+        assert!(InRange::new(0.0, 1.0).validate("0.5").is_ok());
+    }
+
+    #[test]
+    fn checks_addresses() {
+        assert!(Hostname.validate("db-1.example.com").is_ok());
+        assert!(Hostname.validate("-db.example.com").is_err());
+        assert!(Hostname.validate("db..example.com").is_err());
+        assert!(IpAddress.validate("192.168.0.1").is_ok());
+        assert!(IpAddress.validate("::1").is_ok());
+        assert!(IpAddress.validate("192.168.0.256").is_err());
+        assert!(Email.validate("dave@example.com").is_ok());
+        assert!(Email.validate("dave@localhost").is_err());
+        assert!(Email.validate("da ve@example.com").is_err());
+        assert!(Url.validate("https://example.com/path?q=1").is_ok());
+        assert!(Url.validate("example.com").is_err());
+        assert!(Url.validate("https:///path").is_err());
+    }
+
+    #[test]
+    fn checks_paths() {
+        let root = env::temp_dir().join(format!("arsk-validators-{}", process::id()));
+        fs::create_dir_all(&root).unwrap();
+        let file = root.join("file");
+        fs::write(&file, "").unwrap();
+        let (dir, file) = (root.to_str().unwrap(), file.to_str().unwrap());
+        assert!(PathExists.validate(file).is_ok());
+        assert!(IsDir.validate(dir).is_ok());
+        assert!(IsDir.validate(file).is_err());
+        assert!(IsWritable.validate(file).is_ok());
+        assert!(PathExists.validate("/no/such/path").is_err());
+        assert!(IsWritable.validate("/no/such/path").is_err());
+        fs::remove_dir_all(&root).unwrap();
+    }
+
+    #[test]
+    fn combines_validators() {
+        let host = Hostname.or(IpAddress);
+        assert!(host.validate("::1").is_ok());
+        assert!(host.validate("db 1").is_err());
+        let name = NonEmpty.and(Length::at_most(3));
+        assert_eq!(name.validate(""), Err("An answer is required.".to_string()));
+        assert!(name.validate("webby").is_err());
+        let not_root = OneOf::new(&["root"]).not("Pick an unprivileged user.");
+        assert!(not_root.validate("dave").is_ok());
+        assert_eq!(
+            not_root.validate("root"),
+            Err("Pick an unprivileged user.".to_string())
+        );
+    }
+}
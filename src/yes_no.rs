@@ -0,0 +1,182 @@
+use std::fmt::Display;
+use {Result, StateBuilder};
+
+const YES: &[&str] = &["y", "yes"];
+const NO: &[&str] = &["n", "no"];
+
+pub(crate) fn parse_yes_no(yes: &[&str], no: &[&str], answer: &str) -> Option<bool> {
+    let answer = answer.trim().to_lowercase();
+    let matches = |tokens: &[&str]| tokens.iter().any(|t| t.to_lowercase() == answer);
+    match (matches(yes), matches(no)) {
+        (true, false) => Some(true),
+        (false, true) => Some(false),
+        _ => None,
+    }
+}
+
+fn capitalise(token: &str) -> String {
+    let mut chars = token.chars();
+    match chars.next() {
+        Some(first) => first.to_uppercase().chain(chars).collect(),
+        None => String::new(),
+    }
+}
+
+impl<'ask, T: Display + Default> StateBuilder<'ask, T> {
+    pub(crate) fn tokens(&self) -> (&'ask [&'ask str], &'ask [&'ask str]) {
+        (
+            self.state.yes_tokens.unwrap_or(YES),
+            self.state.no_tokens.unwrap_or(NO),
+        )
+    }
+
+    /// The answer `value` stands for: the first of the prompt's own tokens, or
+    /// `yes` or `no` when it was not given any.
+    pub(crate) fn bool_answer(&self, value: bool) -> String {
+        let (tokens, fallback) = match value {
+            true => (self.state.yes_tokens, "yes"),
+            false => (self.state.no_tokens, "no"),
+        };
+        tokens
+            .and_then(|tokens| tokens.first().cloned())
+            .unwrap_or(fallback)
+            .to_string()
+    }
+
+    fn yes_no_hint(&self) -> String {
+        let (yes_tokens, no_tokens) = self.tokens();
+        let default = self
+            .state
+            .default
+            .and_then(|default| parse_yes_no(yes_tokens, no_tokens, default));
+        let yes = yes_tokens.first().unwrap_or(&"");
+        let no = no_tokens.first().unwrap_or(&"");
+        match default {
+            Some(true) => format!("{}/{}", capitalise(yes), no),
+            Some(false) => format!("{}/{}", yes, capitalise(no)),
+            None => format!("{}/{}", yes, no),
+        }
+    }
+
+    /// Shows the yes/no hint, giving the reason an answer that is neither is rejected.
+    fn yes_no_reason(&mut self) -> String {
+        let hint = self.yes_no_hint();
+        let reason = match self.state.validation_message {
+            Some(msg) => msg.to_string(),
+            None => format!(
+                "Please answer {}.",
+                hint.to_lowercase().replace('/', " or ")
+            ),
+        };
+        self.state.hint = Some(hint);
+        reason
+    }
+
+    pub fn ask_yes_no(&mut self) -> Result<bool> {
+        let (yes, no) = self.tokens();
+        let reason = self.yes_no_reason();
+        self.answer(|answer| parse_yes_no(yes, no, &answer).ok_or_else(|| reason.clone()))
+    }
+
+    /// Like `ask_yes_no`, but an empty answer with no default gives `None`.
+    pub(crate) fn ask_optional_yes_no(&mut self) -> Result<Option<bool>> {
+        let (yes, no) = self.tokens();
+        let reason = self.yes_no_reason();
+        self.answer(|answer| match answer.trim() {
+            "" => Ok(None),
+            answer => parse_yes_no(yes, no, answer)
+                .map(Some)
+                .ok_or_else(|| reason.clone()),
+        })
+    }
+
+    pub fn yes_tokens(&mut self, tokens: &'ask [&'ask str]) -> &mut StateBuilder<'ask, T> {
+        self.state.yes_tokens = Some(tokens);
+        self
+    }
+
+    pub fn no_tokens(&mut self, tokens: &'ask [&'ask str]) -> &mut StateBuilder<'ask, T> {
+        self.state.no_tokens = Some(tokens);
+        self
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::parse_yes_no;
+    use testing::Script;
+    use {input, Error};
+
+    #[test]
+    fn parses_tokens_without_case() {
+        assert_eq!(parse_yes_no(&["y", "yes"], &["n"], " YES\n"), Some(true));
+        assert_eq!(parse_yes_no(&["y"], &["n", "no"], "No"), Some(false));
+        assert_eq!(parse_yes_no(&["y"], &["n"], "maybe"), None);
+    }
+
+    #[test]
+    fn can_ask_yes_or_no() {
+        let mut script = Script::new();
+        script
+            .expect("Deploy? [y/n]")
+            .send("maybe")
+            .expect("Please answer y or n.\nDeploy? [y/n]")
+            .send("n");
+        let deploy = script.run(|term| input("Deploy?").terminal(term).ask_yes_no().unwrap());
+        assert!(!deploy);
+    }
+
+    #[test]
+    fn can_answer_with_the_default() {
+        let mut script = Script::new();
+        script
+            .expect("Deploy? [Y/n]")
+            .send("")
+            .expect("Sure? [y/N]")
+            .send_eof();
+        script.run(|term| {
+            assert!(input("Deploy?")
+                .default("y")
+                .terminal(term)
+                .ask_yes_no()
+                .unwrap());
+            assert!(!input("Sure?")
+                .default("no")
+                .terminal(term)
+                .ask_yes_no()
+                .unwrap());
+        });
+    }
+
+    #[test]
+    fn fails_at_end_of_input_without_a_default() {
+        let mut script = Script::new();
+        script.send_eof();
+        let err = input("Deploy?")
+            .terminal(&mut script)
+            .ask_yes_no()
+            .unwrap_err();
+        assert!(matches!(err, Error::Eof));
+    }
+
+    #[test]
+    fn can_change_the_wording() {
+        let mut script = Script::new();
+        script
+            .expect("Continuer? [Oui/non]")
+            .send("ja")
+            .expect("Oui ou non ?\nContinuer? [Oui/non]")
+            .send("NON");
+        let answer = script.run(|term| {
+            input("Continuer?")
+                .yes_tokens(&["oui", "o"])
+                .no_tokens(&["non"])
+                .default("oui")
+                .validation_message("Oui ou non ?")
+                .terminal(term)
+                .ask_yes_no()
+                .unwrap()
+        });
+        assert!(!answer);
+    }
+}